@@ -49,73 +49,73 @@ SEXP savvy_sk_absolute_fill__impl(SEXP c_arg__size, SEXP c_arg__fill) {
     return handle_result(res);
 }
 
-SEXP savvy_sk_as_nativeraster__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes) {
-    SEXP res = savvy_sk_as_nativeraster__ffi(c_arg__size, c_arg__curr_bytes);
+SEXP savvy_sk_as_apng__impl(SEXP c_arg__size, SEXP c_arg__pictures, SEXP c_arg__delays, SEXP c_arg__plays) {
+    SEXP res = savvy_sk_as_apng__ffi(c_arg__size, c_arg__pictures, c_arg__delays, c_arg__plays);
     return handle_result(res);
 }
 
-SEXP savvy_sk_as_png__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes) {
-    SEXP res = savvy_sk_as_png__ffi(c_arg__size, c_arg__curr_bytes);
+SEXP savvy_sk_as_gif__impl(SEXP c_arg__size, SEXP c_arg__pictures, SEXP c_arg__delays, SEXP c_arg__plays, SEXP c_arg__speed, SEXP c_arg__dither) {
+    SEXP res = savvy_sk_as_gif__ffi(c_arg__size, c_arg__pictures, c_arg__delays, c_arg__plays, c_arg__speed, c_arg__dither);
     return handle_result(res);
 }
 
-SEXP savvy_sk_draw_arc__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__ltrb, SEXP c_arg__r, SEXP c_arg__use_center, SEXP c_arg__angle, SEXP c_arg__rsx_trans, SEXP c_arg__sigma, SEXP c_arg__width, SEXP c_arg__color) {
-    SEXP res = savvy_sk_draw_arc__ffi(c_arg__size, c_arg__curr_bytes, c_arg__props, c_arg__ltrb, c_arg__r, c_arg__use_center, c_arg__angle, c_arg__rsx_trans, c_arg__sigma, c_arg__width, c_arg__color);
+SEXP savvy_sk_as_jpeg__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__quality, SEXP c_arg__subsampling, SEXP c_arg__background, SEXP c_arg__scale) {
+    SEXP res = savvy_sk_as_jpeg__ffi(c_arg__size, c_arg__curr_bytes, c_arg__quality, c_arg__subsampling, c_arg__background, c_arg__scale);
     return handle_result(res);
 }
 
-SEXP savvy_sk_draw_atlas__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__png_bytes, SEXP c_arg__rsx_trans) {
-    SEXP res = savvy_sk_draw_atlas__ffi(c_arg__size, c_arg__curr_bytes, c_arg__props, c_arg__png_bytes, c_arg__rsx_trans);
+SEXP savvy_sk_as_nativeraster__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__premultiplied, SEXP c_arg__scale) {
+    SEXP res = savvy_sk_as_nativeraster__ffi(c_arg__size, c_arg__curr_bytes, c_arg__premultiplied, c_arg__scale);
     return handle_result(res);
 }
 
-SEXP savvy_sk_draw_circle__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__x, SEXP c_arg__y, SEXP c_arg__radius, SEXP c_arg__sigma, SEXP c_arg__width, SEXP c_arg__color) {
-    SEXP res = savvy_sk_draw_circle__ffi(c_arg__size, c_arg__curr_bytes, c_arg__props, c_arg__x, c_arg__y, c_arg__radius, c_arg__sigma, c_arg__width, c_arg__color);
+SEXP savvy_sk_as_pdf__impl(SEXP c_arg__size, SEXP c_arg__pictures, SEXP c_arg__page_size, SEXP c_arg__metadata, SEXP c_arg__creation) {
+    SEXP res = savvy_sk_as_pdf__ffi(c_arg__size, c_arg__pictures, c_arg__page_size, c_arg__metadata, c_arg__creation);
     return handle_result(res);
 }
 
-SEXP savvy_sk_draw_diff_rect__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__outer_ltrb, SEXP c_arg__outer_rx, SEXP c_arg__outer_ry, SEXP c_arg__inner_ltrb, SEXP c_arg__inner_rx, SEXP c_arg__inner_ry, SEXP c_arg__rsx_trans, SEXP c_arg__sigma, SEXP c_arg__width, SEXP c_arg__color) {
-    SEXP res = savvy_sk_draw_diff_rect__ffi(c_arg__size, c_arg__curr_bytes, c_arg__props, c_arg__outer_ltrb, c_arg__outer_rx, c_arg__outer_ry, c_arg__inner_ltrb, c_arg__inner_rx, c_arg__inner_ry, c_arg__rsx_trans, c_arg__sigma, c_arg__width, c_arg__color);
+SEXP savvy_sk_as_png__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__compression, SEXP c_arg__filters, SEXP c_arg__scale, SEXP c_arg__color_space, SEXP c_arg__f16) {
+    SEXP res = savvy_sk_as_png__ffi(c_arg__size, c_arg__curr_bytes, c_arg__compression, c_arg__filters, c_arg__scale, c_arg__color_space, c_arg__f16);
     return handle_result(res);
 }
 
-SEXP savvy_sk_draw_line__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__from_x, SEXP c_arg__from_y, SEXP c_arg__to_x, SEXP c_arg__to_y, SEXP c_arg__sigma, SEXP c_arg__width, SEXP c_arg__color) {
-    SEXP res = savvy_sk_draw_line__ffi(c_arg__size, c_arg__curr_bytes, c_arg__props, c_arg__from_x, c_arg__from_y, c_arg__to_x, c_arg__to_y, c_arg__sigma, c_arg__width, c_arg__color);
+SEXP savvy_sk_as_png_tiled__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__path, SEXP c_arg__tile_size, SEXP c_arg__compression, SEXP c_arg__scale) {
+    SEXP res = savvy_sk_as_png_tiled__ffi(c_arg__size, c_arg__curr_bytes, c_arg__path, c_arg__tile_size, c_arg__compression, c_arg__scale);
     return handle_result(res);
 }
 
-SEXP savvy_sk_draw_path__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__svg, SEXP c_arg__rsx_trans, SEXP c_arg__sigma, SEXP c_arg__width, SEXP c_arg__color, SEXP c_arg__fill_type) {
-    SEXP res = savvy_sk_draw_path__ffi(c_arg__size, c_arg__curr_bytes, c_arg__props, c_arg__svg, c_arg__rsx_trans, c_arg__sigma, c_arg__width, c_arg__color, c_arg__fill_type);
+SEXP savvy_sk_as_rgba_array__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__scale) {
+    SEXP res = savvy_sk_as_rgba_array__ffi(c_arg__size, c_arg__curr_bytes, c_arg__scale);
     return handle_result(res);
 }
 
-SEXP savvy_sk_draw_png__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__png_bytes, SEXP c_arg__left_top) {
-    SEXP res = savvy_sk_draw_png__ffi(c_arg__size, c_arg__curr_bytes, c_arg__props, c_arg__png_bytes, c_arg__left_top);
+SEXP savvy_sk_as_svg__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__text_to_path, SEXP c_arg__pretty) {
+    SEXP res = savvy_sk_as_svg__ffi(c_arg__size, c_arg__curr_bytes, c_arg__text_to_path, c_arg__pretty);
     return handle_result(res);
 }
 
-SEXP savvy_sk_draw_points__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__x, SEXP c_arg__y, SEXP c_arg__group, SEXP c_arg__sigma, SEXP c_arg__width, SEXP c_arg__color, SEXP c_arg__mode) {
-    SEXP res = savvy_sk_draw_points__ffi(c_arg__size, c_arg__curr_bytes, c_arg__props, c_arg__x, c_arg__y, c_arg__group, c_arg__sigma, c_arg__width, c_arg__color, c_arg__mode);
+SEXP savvy_sk_as_webp__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__quality, SEXP c_arg__lossless, SEXP c_arg__scale) {
+    SEXP res = savvy_sk_as_webp__ffi(c_arg__size, c_arg__curr_bytes, c_arg__quality, c_arg__lossless, c_arg__scale);
     return handle_result(res);
 }
 
-SEXP savvy_sk_draw_rounded_rect__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__ltrb, SEXP c_arg__rx, SEXP c_arg__ry, SEXP c_arg__rsx_trans, SEXP c_arg__sigma, SEXP c_arg__width, SEXP c_arg__color) {
-    SEXP res = savvy_sk_draw_rounded_rect__ffi(c_arg__size, c_arg__curr_bytes, c_arg__props, c_arg__ltrb, c_arg__rx, c_arg__ry, c_arg__rsx_trans, c_arg__sigma, c_arg__width, c_arg__color);
+SEXP savvy_sk_draw_ops__impl(SEXP c_arg__target, SEXP c_arg__ops) {
+    SEXP res = savvy_sk_draw_ops__ffi(c_arg__target, c_arg__ops);
     return handle_result(res);
 }
 
-SEXP savvy_sk_draw_text__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__text, SEXP c_arg__freeze, SEXP c_arg__rsx_trans, SEXP c_arg__sigma, SEXP c_arg__color) {
-    SEXP res = savvy_sk_draw_text__ffi(c_arg__size, c_arg__curr_bytes, c_arg__props, c_arg__text, c_arg__freeze, c_arg__rsx_trans, c_arg__sigma, c_arg__color);
+SEXP savvy_sk_get_text_info__impl(SEXP c_arg__text, SEXP c_arg__props) {
+    SEXP res = savvy_sk_get_text_info__ffi(c_arg__text, c_arg__props);
     return handle_result(res);
 }
 
-SEXP savvy_sk_draw_vertices__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__x, SEXP c_arg__y, SEXP c_arg__sigma, SEXP c_arg__color, SEXP c_arg__mode) {
-    SEXP res = savvy_sk_draw_vertices__ffi(c_arg__size, c_arg__curr_bytes, c_arg__props, c_arg__x, c_arg__y, c_arg__sigma, c_arg__color, c_arg__mode);
+SEXP savvy_sk_image_diff__impl(SEXP c_arg__size, SEXP c_arg__x, SEXP c_arg__y, SEXP c_arg__threshold, SEXP c_arg__diff) {
+    SEXP res = savvy_sk_image_diff__ffi(c_arg__size, c_arg__x, c_arg__y, c_arg__threshold, c_arg__diff);
     return handle_result(res);
 }
 
-SEXP savvy_sk_get_text_info__impl(SEXP c_arg__text, SEXP c_arg__props) {
-    SEXP res = savvy_sk_get_text_info__ffi(c_arg__text, c_arg__props);
+SEXP savvy_sk_image_info__impl(SEXP c_arg__bytes) {
+    SEXP res = savvy_sk_image_info__ffi(c_arg__bytes);
     return handle_result(res);
 }
 
@@ -144,6 +144,31 @@ SEXP savvy_sk_path_transform__impl(SEXP c_arg__svg, SEXP c_arg__mat) {
     return handle_result(res);
 }
 
+SEXP savvy_sk_picture_info__impl(SEXP c_arg__picture) {
+    SEXP res = savvy_sk_picture_info__ffi(c_arg__picture);
+    return handle_result(res);
+}
+
+SEXP savvy_sk_picture_ops__impl(SEXP c_arg__picture) {
+    SEXP res = savvy_sk_picture_ops__ffi(c_arg__picture);
+    return handle_result(res);
+}
+
+SEXP savvy_sk_register_font__impl(SEXP c_arg__data, SEXP c_arg__alias) {
+    SEXP res = savvy_sk_register_font__ffi(c_arg__data, c_arg__alias);
+    return handle_result(res);
+}
+
+SEXP savvy_sk_set_max_pixels__impl(SEXP c_arg__max_pixels) {
+    SEXP res = savvy_sk_set_max_pixels__ffi(c_arg__max_pixels);
+    return handle_result(res);
+}
+
+SEXP savvy_sk_text_to_svg__impl(SEXP c_arg__text, SEXP c_arg__props, SEXP c_arg__per_glyph) {
+    SEXP res = savvy_sk_text_to_svg__ffi(c_arg__text, c_arg__props, c_arg__per_glyph);
+    return handle_result(res);
+}
+
 
 
 
@@ -356,6 +381,21 @@ SEXP savvy_Shader_turbulence__impl(SEXP c_arg__freq, SEXP c_arg__octaves, SEXP c
     return handle_result(res);
 }
 
+SEXP savvy_SkiaCanvas_from_picture__impl(SEXP c_arg__size, SEXP c_arg__curr_bytes) {
+    SEXP res = savvy_SkiaCanvas_from_picture__ffi(c_arg__size, c_arg__curr_bytes);
+    return handle_result(res);
+}
+
+SEXP savvy_SkiaCanvas_new__impl(SEXP c_arg__size, SEXP c_arg__fill) {
+    SEXP res = savvy_SkiaCanvas_new__ffi(c_arg__size, c_arg__fill);
+    return handle_result(res);
+}
+
+SEXP savvy_SkiaCanvas_snapshot__impl(SEXP self__) {
+    SEXP res = savvy_SkiaCanvas_snapshot__ffi(self__);
+    return handle_result(res);
+}
+
 
 
 
@@ -363,25 +403,30 @@ SEXP savvy_Shader_turbulence__impl(SEXP c_arg__freq, SEXP c_arg__octaves, SEXP c
 static const R_CallMethodDef CallEntries[] = {
     {"savvy_op_count__impl", (DL_FUNC) &savvy_op_count__impl, 2},
     {"savvy_sk_absolute_fill__impl", (DL_FUNC) &savvy_sk_absolute_fill__impl, 2},
-    {"savvy_sk_as_nativeraster__impl", (DL_FUNC) &savvy_sk_as_nativeraster__impl, 2},
-    {"savvy_sk_as_png__impl", (DL_FUNC) &savvy_sk_as_png__impl, 2},
-    {"savvy_sk_draw_arc__impl", (DL_FUNC) &savvy_sk_draw_arc__impl, 11},
-    {"savvy_sk_draw_atlas__impl", (DL_FUNC) &savvy_sk_draw_atlas__impl, 5},
-    {"savvy_sk_draw_circle__impl", (DL_FUNC) &savvy_sk_draw_circle__impl, 9},
-    {"savvy_sk_draw_diff_rect__impl", (DL_FUNC) &savvy_sk_draw_diff_rect__impl, 13},
-    {"savvy_sk_draw_line__impl", (DL_FUNC) &savvy_sk_draw_line__impl, 10},
-    {"savvy_sk_draw_path__impl", (DL_FUNC) &savvy_sk_draw_path__impl, 9},
-    {"savvy_sk_draw_png__impl", (DL_FUNC) &savvy_sk_draw_png__impl, 5},
-    {"savvy_sk_draw_points__impl", (DL_FUNC) &savvy_sk_draw_points__impl, 10},
-    {"savvy_sk_draw_rounded_rect__impl", (DL_FUNC) &savvy_sk_draw_rounded_rect__impl, 10},
-    {"savvy_sk_draw_text__impl", (DL_FUNC) &savvy_sk_draw_text__impl, 8},
-    {"savvy_sk_draw_vertices__impl", (DL_FUNC) &savvy_sk_draw_vertices__impl, 8},
+    {"savvy_sk_as_apng__impl", (DL_FUNC) &savvy_sk_as_apng__impl, 4},
+    {"savvy_sk_as_gif__impl", (DL_FUNC) &savvy_sk_as_gif__impl, 6},
+    {"savvy_sk_as_jpeg__impl", (DL_FUNC) &savvy_sk_as_jpeg__impl, 6},
+    {"savvy_sk_as_nativeraster__impl", (DL_FUNC) &savvy_sk_as_nativeraster__impl, 4},
+    {"savvy_sk_as_pdf__impl", (DL_FUNC) &savvy_sk_as_pdf__impl, 5},
+    {"savvy_sk_as_png__impl", (DL_FUNC) &savvy_sk_as_png__impl, 7},
+    {"savvy_sk_as_png_tiled__impl", (DL_FUNC) &savvy_sk_as_png_tiled__impl, 6},
+    {"savvy_sk_as_rgba_array__impl", (DL_FUNC) &savvy_sk_as_rgba_array__impl, 3},
+    {"savvy_sk_as_svg__impl", (DL_FUNC) &savvy_sk_as_svg__impl, 4},
+    {"savvy_sk_as_webp__impl", (DL_FUNC) &savvy_sk_as_webp__impl, 5},
+    {"savvy_sk_draw_ops__impl", (DL_FUNC) &savvy_sk_draw_ops__impl, 2},
     {"savvy_sk_get_text_info__impl", (DL_FUNC) &savvy_sk_get_text_info__impl, 2},
+    {"savvy_sk_image_diff__impl", (DL_FUNC) &savvy_sk_image_diff__impl, 5},
+    {"savvy_sk_image_info__impl", (DL_FUNC) &savvy_sk_image_info__impl, 1},
     {"savvy_sk_list_families__impl", (DL_FUNC) &savvy_sk_list_families__impl, 0},
     {"savvy_sk_matrix_map_point__impl", (DL_FUNC) &savvy_sk_matrix_map_point__impl, 4},
     {"savvy_sk_path_bounds__impl", (DL_FUNC) &savvy_sk_path_bounds__impl, 1},
     {"savvy_sk_path_interpolate__impl", (DL_FUNC) &savvy_sk_path_interpolate__impl, 3},
     {"savvy_sk_path_transform__impl", (DL_FUNC) &savvy_sk_path_transform__impl, 2},
+    {"savvy_sk_picture_info__impl", (DL_FUNC) &savvy_sk_picture_info__impl, 1},
+    {"savvy_sk_picture_ops__impl", (DL_FUNC) &savvy_sk_picture_ops__impl, 1},
+    {"savvy_sk_register_font__impl", (DL_FUNC) &savvy_sk_register_font__impl, 2},
+    {"savvy_sk_set_max_pixels__impl", (DL_FUNC) &savvy_sk_set_max_pixels__impl, 1},
+    {"savvy_sk_text_to_svg__impl", (DL_FUNC) &savvy_sk_text_to_svg__impl, 3},
 
 
 
@@ -430,6 +475,9 @@ static const R_CallMethodDef CallEntries[] = {
     {"savvy_Shader_radial_gradient__impl", (DL_FUNC) &savvy_Shader_radial_gradient__impl, 6},
     {"savvy_Shader_sweep_gradient__impl", (DL_FUNC) &savvy_Shader_sweep_gradient__impl, 7},
     {"savvy_Shader_turbulence__impl", (DL_FUNC) &savvy_Shader_turbulence__impl, 4},
+    {"savvy_SkiaCanvas_from_picture__impl", (DL_FUNC) &savvy_SkiaCanvas_from_picture__impl, 2},
+    {"savvy_SkiaCanvas_new__impl", (DL_FUNC) &savvy_SkiaCanvas_new__impl, 2},
+    {"savvy_SkiaCanvas_snapshot__impl", (DL_FUNC) &savvy_SkiaCanvas_snapshot__impl, 1},
 
 
 
@@ -1,24 +1,29 @@
 SEXP savvy_op_count__ffi(SEXP c_arg__picture, SEXP c_arg__nested);
 SEXP savvy_sk_absolute_fill__ffi(SEXP c_arg__size, SEXP c_arg__fill);
-SEXP savvy_sk_as_nativeraster__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes);
-SEXP savvy_sk_as_png__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes);
-SEXP savvy_sk_draw_arc__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__ltrb, SEXP c_arg__r, SEXP c_arg__use_center, SEXP c_arg__angle, SEXP c_arg__rsx_trans, SEXP c_arg__sigma, SEXP c_arg__width, SEXP c_arg__color);
-SEXP savvy_sk_draw_atlas__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__png_bytes, SEXP c_arg__rsx_trans);
-SEXP savvy_sk_draw_circle__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__x, SEXP c_arg__y, SEXP c_arg__radius, SEXP c_arg__sigma, SEXP c_arg__width, SEXP c_arg__color);
-SEXP savvy_sk_draw_diff_rect__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__outer_ltrb, SEXP c_arg__outer_rx, SEXP c_arg__outer_ry, SEXP c_arg__inner_ltrb, SEXP c_arg__inner_rx, SEXP c_arg__inner_ry, SEXP c_arg__rsx_trans, SEXP c_arg__sigma, SEXP c_arg__width, SEXP c_arg__color);
-SEXP savvy_sk_draw_line__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__from_x, SEXP c_arg__from_y, SEXP c_arg__to_x, SEXP c_arg__to_y, SEXP c_arg__sigma, SEXP c_arg__width, SEXP c_arg__color);
-SEXP savvy_sk_draw_path__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__svg, SEXP c_arg__rsx_trans, SEXP c_arg__sigma, SEXP c_arg__width, SEXP c_arg__color, SEXP c_arg__fill_type);
-SEXP savvy_sk_draw_png__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__png_bytes, SEXP c_arg__left_top);
-SEXP savvy_sk_draw_points__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__x, SEXP c_arg__y, SEXP c_arg__group, SEXP c_arg__sigma, SEXP c_arg__width, SEXP c_arg__color, SEXP c_arg__mode);
-SEXP savvy_sk_draw_rounded_rect__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__ltrb, SEXP c_arg__rx, SEXP c_arg__ry, SEXP c_arg__rsx_trans, SEXP c_arg__sigma, SEXP c_arg__width, SEXP c_arg__color);
-SEXP savvy_sk_draw_text__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__text, SEXP c_arg__freeze, SEXP c_arg__rsx_trans, SEXP c_arg__sigma, SEXP c_arg__color);
-SEXP savvy_sk_draw_vertices__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__props, SEXP c_arg__x, SEXP c_arg__y, SEXP c_arg__sigma, SEXP c_arg__color, SEXP c_arg__mode);
+SEXP savvy_sk_as_apng__ffi(SEXP c_arg__size, SEXP c_arg__pictures, SEXP c_arg__delays, SEXP c_arg__plays);
+SEXP savvy_sk_as_gif__ffi(SEXP c_arg__size, SEXP c_arg__pictures, SEXP c_arg__delays, SEXP c_arg__plays, SEXP c_arg__speed, SEXP c_arg__dither);
+SEXP savvy_sk_as_jpeg__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__quality, SEXP c_arg__subsampling, SEXP c_arg__background, SEXP c_arg__scale);
+SEXP savvy_sk_as_nativeraster__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__premultiplied, SEXP c_arg__scale);
+SEXP savvy_sk_as_pdf__ffi(SEXP c_arg__size, SEXP c_arg__pictures, SEXP c_arg__page_size, SEXP c_arg__metadata, SEXP c_arg__creation);
+SEXP savvy_sk_as_png__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__compression, SEXP c_arg__filters, SEXP c_arg__scale, SEXP c_arg__color_space, SEXP c_arg__f16);
+SEXP savvy_sk_as_png_tiled__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__path, SEXP c_arg__tile_size, SEXP c_arg__compression, SEXP c_arg__scale);
+SEXP savvy_sk_as_rgba_array__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__scale);
+SEXP savvy_sk_as_svg__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__text_to_path, SEXP c_arg__pretty);
+SEXP savvy_sk_as_webp__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes, SEXP c_arg__quality, SEXP c_arg__lossless, SEXP c_arg__scale);
+SEXP savvy_sk_draw_ops__ffi(SEXP c_arg__target, SEXP c_arg__ops);
 SEXP savvy_sk_get_text_info__ffi(SEXP c_arg__text, SEXP c_arg__props);
+SEXP savvy_sk_image_diff__ffi(SEXP c_arg__size, SEXP c_arg__x, SEXP c_arg__y, SEXP c_arg__threshold, SEXP c_arg__diff);
+SEXP savvy_sk_image_info__ffi(SEXP c_arg__bytes);
 SEXP savvy_sk_list_families__ffi(void);
 SEXP savvy_sk_matrix_map_point__ffi(SEXP c_arg__src_x, SEXP c_arg__src_y, SEXP c_arg__dst_x, SEXP c_arg__dst_y);
 SEXP savvy_sk_path_bounds__ffi(SEXP c_arg__svg);
 SEXP savvy_sk_path_interpolate__ffi(SEXP c_arg__value, SEXP c_arg__first, SEXP c_arg__second);
 SEXP savvy_sk_path_transform__ffi(SEXP c_arg__svg, SEXP c_arg__mat);
+SEXP savvy_sk_picture_info__ffi(SEXP c_arg__picture);
+SEXP savvy_sk_picture_ops__ffi(SEXP c_arg__picture);
+SEXP savvy_sk_register_font__ffi(SEXP c_arg__data, SEXP c_arg__alias);
+SEXP savvy_sk_set_max_pixels__ffi(SEXP c_arg__max_pixels);
+SEXP savvy_sk_text_to_svg__ffi(SEXP c_arg__text, SEXP c_arg__props, SEXP c_arg__per_glyph);
 
 // methods and associated functions for ImageFilter
 SEXP savvy_ImageFilter_arithmetic__ffi(SEXP c_arg__dst, SEXP c_arg__src, SEXP c_arg__coef, SEXP c_arg__crop_rect);
@@ -70,3 +75,8 @@ SEXP savvy_Shader_no_shader__ffi(void);
 SEXP savvy_Shader_radial_gradient__ffi(SEXP c_arg__center, SEXP c_arg__radius, SEXP c_arg__color, SEXP c_arg__mode, SEXP c_arg__flags, SEXP c_arg__transform);
 SEXP savvy_Shader_sweep_gradient__ffi(SEXP c_arg__center, SEXP c_arg__start_angle, SEXP c_arg__end_angle, SEXP c_arg__color, SEXP c_arg__mode, SEXP c_arg__flags, SEXP c_arg__transform);
 SEXP savvy_Shader_turbulence__ffi(SEXP c_arg__freq, SEXP c_arg__octaves, SEXP c_arg__seed, SEXP c_arg__tile_size);
+
+// methods and associated functions for SkiaCanvas
+SEXP savvy_SkiaCanvas_from_picture__ffi(SEXP c_arg__size, SEXP c_arg__curr_bytes);
+SEXP savvy_SkiaCanvas_new__ffi(SEXP c_arg__size, SEXP c_arg__fill);
+SEXP savvy_SkiaCanvas_snapshot__ffi(SEXP self__);
@@ -0,0 +1,175 @@
+use savvy::savvy_err;
+
+use crate::canvas::{rasterize, read_rgba_pixels};
+
+/// Rasterizes pictures into frames of non-premultiplied RGBA8888 pixels
+fn read_frames(
+    size: &[i32],
+    pictures: &[skia_safe::Picture],
+) -> anyhow::Result<Vec<Vec<u8>>, savvy::Error> {
+    pictures
+        .iter()
+        .map(|picture| {
+            let image = rasterize(size.to_vec(), picture, None)?;
+            read_rgba_pixels(&image, skia_safe::AlphaType::Unpremul)
+        })
+        .collect()
+}
+
+/// Encodes pictures into an animated PNG
+///
+/// `delays` are in seconds and `plays` is the number of times to play the animation,
+/// where 0 means infinite looping.
+pub fn as_apng(
+    size: Vec<i32>,
+    pictures: Vec<skia_safe::Picture>,
+    delays: &[f64],
+    plays: u32,
+) -> anyhow::Result<Vec<u8>, savvy::Error> {
+    let frames = read_frames(&size, &pictures)?;
+
+    let mut buf: Vec<u8> = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut buf, size[0] as u32, size[1] as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .set_animated(frames.len() as u32, plays)
+            .map_err(|e| savvy_err!("Failed to encode APNG:\n {}", e))?;
+        let mut writer = encoder
+            .write_header()
+            .map_err(|e| savvy_err!("Failed to encode APNG:\n {}", e))?;
+        for (frame, delay) in frames.iter().zip(delays.iter()) {
+            let millis = (delay * 1000.0).round().clamp(0.0, u16::MAX as f64) as u16;
+            writer
+                .set_frame_delay(millis, 1000)
+                .map_err(|e| savvy_err!("Failed to encode APNG:\n {}", e))?;
+            writer
+                .write_image_data(frame)
+                .map_err(|e| savvy_err!("Failed to encode APNG:\n {}", e))?;
+        }
+        writer
+            .finish()
+            .map_err(|e| savvy_err!("Failed to encode APNG:\n {}", e))?;
+    }
+    Ok(buf)
+}
+
+/// Index of the palette entry reserved for transparent pixels
+const GIF_TRANSPARENT: u8 = 255;
+
+/// Quantizes a frame into palette indices
+///
+/// Pixels with alpha less than 128 are treated as transparent,
+/// because GIF only supports fully transparent or opaque pixels.
+/// Returns a palette of RGB colors and indices to the palette.
+fn quantize_frame(
+    pixels: &[u8],
+    width: usize,
+    height: usize,
+    speed: i32,
+    dither: bool,
+) -> (Vec<u8>, Vec<u8>) {
+    let opaque: Vec<u8> = pixels
+        .chunks(4)
+        .filter(|px| px[3] >= 128)
+        .flat_map(|px| [px[0], px[1], px[2], 255])
+        .collect();
+    if opaque.is_empty() {
+        return (vec![0; 256 * 3], vec![GIF_TRANSPARENT; width * height]);
+    }
+    let nq = color_quant::NeuQuant::new(speed.clamp(1, 30), GIF_TRANSPARENT as usize, &opaque);
+    let mut palette = nq.color_map_rgb();
+    palette.resize(256 * 3, 0);
+
+    // Floyd-Steinberg error diffusion.
+    // Errors are only diffused when dithering is enabled.
+    let mut errors = vec![0.0f32; width * height * 3];
+    let mut indices = vec![GIF_TRANSPARENT; width * height];
+    for y in 0..height {
+        for x in 0..width {
+            let i = y * width + x;
+            let px = &pixels[i * 4..i * 4 + 4];
+            if px[3] < 128 {
+                continue;
+            }
+            let color: Vec<f32> = (0..3)
+                .map(|c| (px[c] as f32 + errors[i * 3 + c]).clamp(0.0, 255.0))
+                .collect();
+            let index = nq.index_of(&[color[0] as u8, color[1] as u8, color[2] as u8, 255]);
+            indices[i] = index as u8;
+            if !dither {
+                continue;
+            }
+            for c in 0..3 {
+                let err = color[c] - palette[index * 3 + c] as f32;
+                let mut diffuse = |dx: isize, dy: usize, weight: f32| {
+                    let nx = x as isize + dx;
+                    let ny = y + dy;
+                    if nx >= 0 && (nx as usize) < width && ny < height {
+                        errors[(ny * width + nx as usize) * 3 + c] += err * weight;
+                    }
+                };
+                diffuse(1, 0, 7.0 / 16.0);
+                diffuse(-1, 1, 3.0 / 16.0);
+                diffuse(0, 1, 5.0 / 16.0);
+                diffuse(1, 1, 1.0 / 16.0);
+            }
+        }
+    }
+    (palette, indices)
+}
+
+/// Encodes pictures into an animated GIF
+///
+/// Each frame is quantized to its own palette of up to 255 colors.
+/// `speed` is the sampling factor of the quantizer in 1-30,
+/// where lower values give better quality but are slower.
+pub fn as_gif(
+    size: Vec<i32>,
+    pictures: Vec<skia_safe::Picture>,
+    delays: &[f64],
+    plays: u32,
+    speed: i32,
+    dither: bool,
+) -> anyhow::Result<Vec<u8>, savvy::Error> {
+    if size[0] > u16::MAX as i32 || size[1] > u16::MAX as i32 {
+        return Err(savvy_err!("Canvas size is too large for GIF"));
+    }
+    let (width, height) = (size[0] as u16, size[1] as u16);
+    let frames = read_frames(&size, &pictures)?;
+
+    let mut buf: Vec<u8> = Vec::new();
+    {
+        let mut encoder = gif::Encoder::new(&mut buf, width, height, &[])
+            .map_err(|e| savvy_err!("Failed to encode GIF:\n {}", e))?;
+        let repeat = match plays {
+            0 => Some(gif::Repeat::Infinite),
+            1 => None,
+            n => Some(gif::Repeat::Finite((n - 1).min(u16::MAX as u32) as u16)),
+        };
+        if let Some(repeat) = repeat {
+            encoder
+                .set_repeat(repeat)
+                .map_err(|e| savvy_err!("Failed to encode GIF:\n {}", e))?;
+        }
+        for (frame, delay) in frames.iter().zip(delays.iter()) {
+            let (palette, indices) =
+                quantize_frame(frame, width as usize, height as usize, speed, dither);
+            let frame = gif::Frame {
+                width,
+                height,
+                delay: (delay * 100.0).round().clamp(0.0, u16::MAX as f64) as u16,
+                dispose: gif::DisposalMethod::Background,
+                transparent: Some(GIF_TRANSPARENT),
+                palette: Some(palette),
+                buffer: std::borrow::Cow::Owned(indices),
+                ..Default::default()
+            };
+            encoder
+                .write_frame(&frame)
+                .map_err(|e| savvy_err!("Failed to encode GIF:\n {}", e))?;
+        }
+    }
+    Ok(buf)
+}
@@ -1,7 +1,67 @@
-use crate::paint_attrs::{assert_len, PaintAttrs};
+use crate::paint_attrs::{assert_len, num2colors, PaintAttrs};
 
-use savvy::{savvy, savvy_err};
+use savvy::{savvy, savvy_err, IntegerSexp, NumericSexp, RawSexp};
 use skia_safe::{Picture, PictureRecorder};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Default maximum number of pixels of a canvas (16384 x 16384)
+const DEFAULT_MAX_PIXELS: u64 = 16384 * 16384;
+
+static MAX_PIXELS: AtomicU64 = AtomicU64::new(DEFAULT_MAX_PIXELS);
+
+/// Validates a canvas size and returns it as (width, height)
+///
+/// Every entry point that creates a canvas or a surface goes through this,
+/// so that invalid sizes are reported as errors instead of being replaced or panicking.
+pub fn validate_size(size: &[i32]) -> anyhow::Result<(i32, i32), savvy::Error> {
+    assert_len("size", 2, size.len())?;
+
+    let (width, height) = (size[0], size[1]);
+    if width == i32::MIN || height == i32::MIN {
+        return Err(savvy_err!("Canvas size must not be NA"));
+    }
+    if width <= 0 || height <= 0 {
+        return Err(savvy_err!(
+            "Canvas size must be positive, but got {}x{}",
+            width,
+            height
+        ));
+    }
+    // Row bytes of a 32-bit surface must fit in i32
+    if (width as u64) * 4 > i32::MAX as u64 {
+        return Err(savvy_err!("Canvas width {} is too large", width));
+    }
+    let pixels = (width as u64) * (height as u64);
+    let max_pixels = MAX_PIXELS.load(Ordering::Relaxed);
+    if pixels > max_pixels {
+        return Err(savvy_err!(
+            "Canvas size {}x{} ({} pixels) exceeds the maximum of {} pixels",
+            width,
+            height,
+            pixels,
+            max_pixels
+        ));
+    }
+    Ok((width, height))
+}
+
+/// Sets the maximum number of pixels of a canvas
+///
+/// @param max_pixels Maximum number of pixels. If not positive, the default is restored.
+/// @returns The previous value.
+/// @noRd
+#[savvy]
+fn sk_set_max_pixels(max_pixels: savvy::NumericScalar) -> savvy::Result<savvy::Sexp> {
+    let value = max_pixels.as_f64();
+    let value = if value.is_nan() || value < 1.0 {
+        DEFAULT_MAX_PIXELS
+    } else {
+        value as u64
+    };
+    let prev = MAX_PIXELS.swap(value, Ordering::Relaxed);
+    let ret = savvy::OwnedRealSexp::try_from_scalar(prev as f64)?;
+    Ok(ret.into())
+}
 
 /// Returns a skia_safe::Picture
 pub fn read_picture_bytes(bytes: &savvy::RawSexp) -> anyhow::Result<Picture, savvy::Error> {
@@ -10,24 +70,100 @@ pub fn read_picture_bytes(bytes: &savvy::RawSexp) -> anyhow::Result<Picture, sav
     Ok(picture)
 }
 
+/// Serializes a skia_safe::Picture into a raw vector
+pub fn write_picture_bytes(picture: &Picture) -> anyhow::Result<savvy::OwnedRawSexp, savvy::Error> {
+    let d = picture.serialize();
+    let ret = savvy::OwnedRawSexp::try_from_slice(d.as_bytes())?;
+    Ok(ret)
+}
+
+/// Kind of a canvas state saved by `begin_layer()` and the like
+#[derive(Clone, Copy, PartialEq)]
+pub enum SaveKind {
+    Layer,
+    Clip,
+    Transform,
+}
+
+impl SaveKind {
+    /// Returns a kind from its name passed from R
+    pub fn from_name(name: &str) -> anyhow::Result<Self, savvy::Error> {
+        match name {
+            "layer" => Ok(SaveKind::Layer),
+            "clip" => Ok(SaveKind::Clip),
+            "transform" => Ok(SaveKind::Transform),
+            _ => Err(savvy_err!("Unknown saved state: {}", name)),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SaveKind::Layer => "layer",
+            SaveKind::Clip => "clip",
+            SaveKind::Transform => "transform",
+        }
+    }
+}
+
+/// SkiaCanvas
+///
+/// Internal impl that wraps `skia_safe::PictureRecorder`.
+/// The recording is kept alive between draw calls,
+/// so that shapes can be appended without replaying the whole picture each time.
+/// Use `SkiaCanvas$snapshot()` to get the serialized picture only when needed.
+///
+/// @noRd
+#[savvy]
 pub struct SkiaCanvas {
     width: i32,
     height: i32,
     pub recorder: PictureRecorder,
+    last: Option<Picture>,
+    /// Kinds of the states saved on the recording canvas, from the outermost
+    saves: Vec<SaveKind>,
+}
+
+#[savvy]
+impl SkiaCanvas {
+    fn new(size: IntegerSexp, fill: NumericSexp) -> savvy::Result<Self> {
+        let fill =
+            num2colors(&fill).ok_or_else(|| return savvy_err!("Failed to parse color"))?;
+        let mut recorder = SkiaCanvas::setup(&size)?;
+        let canvas = recorder.start_recording();
+        canvas.clear(fill[0]);
+        Ok(recorder)
+    }
+    fn from_picture(size: IntegerSexp, curr_bytes: RawSexp) -> savvy::Result<Self> {
+        let picture = read_picture_bytes(&curr_bytes)?;
+        let mut recorder = SkiaCanvas::setup(&size)?;
+        recorder.last = Some(picture);
+        Ok(recorder)
+    }
+    fn snapshot(&mut self) -> savvy::Result<savvy::Sexp> {
+        let picture = self.picture()?;
+        let ret = write_picture_bytes(&picture)?;
+        Ok(ret.into())
+    }
 }
 
 impl SkiaCanvas {
-    #[allow(unused_mut)]
     pub fn setup(size: &savvy::IntegerSexp) -> anyhow::Result<SkiaCanvas, savvy::Error> {
-        assert_len("size", 2, size.len())?;
+        let (width, height) = validate_size(size.as_slice())?;
+        Ok(SkiaCanvas::with_size(width, height))
+    }
 
-        let size = size.as_slice();
-        let mut recorder = skia_safe::PictureRecorder::new();
-        Ok(SkiaCanvas {
-            width: size[0],
-            height: size[1],
-            recorder,
-        })
+    pub fn with_size(width: i32, height: i32) -> SkiaCanvas {
+        SkiaCanvas {
+            width,
+            height,
+            recorder: skia_safe::PictureRecorder::new(),
+            last: None,
+            saves: Vec::new(),
+        }
+    }
+
+    pub fn size(&self) -> (i32, i32) {
+        (self.width, self.height)
     }
 
     pub fn start_recording(&mut self) -> &skia_safe::Canvas {
@@ -37,56 +173,430 @@ impl SkiaCanvas {
         canvas
     }
 
-    pub fn finish_recording(&mut self) -> anyhow::Result<savvy::OwnedRawSexp, savvy::Error> {
+    /// Returns the canvas being recorded.
+    /// If the recording has been finished by a snapshot, it is resumed from that picture.
+    pub fn recording_canvas(&mut self) -> anyhow::Result<&skia_safe::Canvas, savvy::Error> {
+        if self.recorder.recording_canvas().is_none() {
+            let last = self.last.take();
+            let canvas = self.start_recording();
+            if let Some(picture) = last {
+                picture.playback(canvas);
+            }
+        }
+        self.recorder
+            .recording_canvas()
+            .ok_or_else(|| return savvy_err!("Failed to get recording canvas"))
+    }
+
+    /// Records that a state of `kind` has been saved on the recording canvas
+    pub fn push_save(&mut self, kind: SaveKind) {
+        self.saves.push(kind);
+    }
+
+    /// Restores the last saved state, which must be of `kind`
+    pub fn restore(&mut self, kind: SaveKind) -> anyhow::Result<(), savvy::Error> {
+        match self.saves.last() {
+            None => return Err(savvy_err!("No saved state to restore")),
+            Some(last) if *last != kind => {
+                return Err(savvy_err!(
+                    "Cannot end a {} while a {} is open; call end_{}() first",
+                    kind.name(),
+                    last.name(),
+                    last.name()
+                ));
+            }
+            _ => {}
+        }
+        self.recording_canvas()?.restore();
+        self.saves.pop();
+        Ok(())
+    }
+
+    /// Finishes the recording and returns the picture.
+    /// The picture is kept so that the next draw call can resume from it.
+    ///
+    /// Finishing the recording would close the saved states,
+    /// so this fails while any of them is open.
+    pub fn picture(&mut self) -> anyhow::Result<Picture, savvy::Error> {
+        if let Some(picture) = &self.last {
+            return Ok(picture.clone());
+        }
+        if let Some(last) = self.saves.last() {
+            return Err(savvy_err!(
+                "Cannot take a snapshot while a {} is open; call end_{}() first",
+                last.name(),
+                last.name()
+            ));
+        }
+        self.recording_canvas()?;
         let picture = self
             .recorder
             .finish_recording_as_picture(None)
             .ok_or_else(|| return savvy_err!("Failed to finish recording"))?;
-        let d = picture.serialize();
-        let bytes = d.as_bytes();
-        let mut ret = savvy::OwnedRawSexp::new(bytes.len())?;
-        for (i, b) in bytes.iter().enumerate() {
-            ret.set_elt(i, *b)?;
+        self.last = Some(picture.clone());
+        Ok(picture)
+    }
+
+    pub fn finish_recording(&mut self) -> anyhow::Result<savvy::OwnedRawSexp, savvy::Error> {
+        let picture = self.picture()?;
+        write_picture_bytes(&picture)
+    }
+}
+
+/// Renders a skia_safe::Picture into a raster image
+///
+/// If `background` is given, the canvas is filled with it before drawing the picture.
+pub fn rasterize(
+    size: Vec<i32>,
+    picture: &skia_safe::Picture,
+    background: Option<skia_safe::Color>,
+) -> anyhow::Result<skia_safe::Image, savvy::Error> {
+    let options = RasterOptions {
+        background,
+        ..Default::default()
+    };
+    rasterize_with(size, picture, &options)
+}
+
+/// Options for rendering pictures into raster images
+pub struct RasterOptions {
+    /// Color to fill the canvas with before drawing the picture
+    pub background: Option<skia_safe::Color>,
+    /// Device scale factor
+    pub scale: f64,
+    /// Working color space of the surface. If `None`, the surface is untagged.
+    pub color_space: Option<skia_safe::ColorSpace>,
+    /// Whether to render into RGBA F16 instead of 8-bit colors
+    pub f16: bool,
+}
+
+impl Default for RasterOptions {
+    fn default() -> Self {
+        RasterOptions {
+            background: None,
+            scale: 1.0,
+            color_space: None,
+            f16: false,
         }
-        Ok(ret)
     }
 }
 
-/// Encodes a skia_safe::Picture into PNG, returning an Option<skia_safe::Data>
-pub fn as_png(size: Vec<i32>, picture: skia_safe::Picture) -> Option<skia_safe::Data> {
-    let mut surface = skia_safe::surfaces::raster_n32_premul((size[0], size[1]))
-        .unwrap_or_else(|| skia_safe::surfaces::raster_n32_premul((768, 576)).unwrap());
-    picture.playback(surface.canvas());
+/// Renders a skia_safe::Picture into a raster image with the specified options
+///
+/// The surface is `scale` times larger than `size`,
+/// and the canvas is scaled before playback so that the picture fills the surface.
+pub fn rasterize_with(
+    size: Vec<i32>,
+    picture: &skia_safe::Picture,
+    options: &RasterOptions,
+) -> anyhow::Result<skia_safe::Image, savvy::Error> {
+    let scale = options.scale;
+    if !scale.is_finite() || scale <= 0.0 {
+        return Err(savvy_err!("scale must be a positive number, but got {}", scale));
+    }
+    let (width, height) = validate_size(&size)?;
+    let scaled = [
+        (width as f64 * scale).round().min(i32::MAX as f64) as i32,
+        (height as f64 * scale).round().min(i32::MAX as f64) as i32,
+    ];
+    let (width, height) = validate_size(&scaled)?;
+    let info = if options.f16 {
+        skia_safe::ImageInfo::new(
+            (width, height),
+            skia_safe::ColorType::RGBAF16,
+            skia_safe::AlphaType::Premul,
+            options.color_space.clone(),
+        )
+    } else {
+        skia_safe::ImageInfo::new_n32_premul((width, height), options.color_space.clone())
+    };
+    let mut surface = skia_safe::surfaces::raster(&info, None, None).ok_or_else(|| {
+        return savvy_err!("Failed to create raster surface of {}x{}", width, height)
+    })?;
+    let canvas = surface.canvas();
+    if let Some(color) = options.background {
+        canvas.clear(color);
+    }
+    canvas.scale((scale as f32, scale as f32));
+    picture.playback(canvas);
+
+    Ok(surface.image_snapshot())
+}
 
-    let image = surface.image_snapshot();
-    let mut context = surface.direct_context();
-    let data = image.encode(context.as_mut(), skia_safe::EncodedImageFormat::PNG, None);
+/// Returns skia_safe::ColorSpace from its name
+pub fn color_space(name: &str) -> anyhow::Result<skia_safe::ColorSpace, savvy::Error> {
+    use skia_safe::{named_primaries, named_transfer_fn, ColorSpace};
 
-    data
+    let ret = match name {
+        "srgb" => Some(ColorSpace::new_srgb()),
+        "linear-srgb" => Some(ColorSpace::new_srgb_linear()),
+        "display-p3" => ColorSpace::new_cicp(
+            named_primaries::CicpId::SMPTE_EG_432_1,
+            named_transfer_fn::CicpId::IEC61966_2_1,
+        ),
+        // Rec. 2020 uses the same transfer function as Rec. 709
+        "rec2020" => ColorSpace::new_cicp(
+            named_primaries::CicpId::Rec2020,
+            named_transfer_fn::CicpId::Rec709,
+        ),
+        _ => return Err(savvy_err!("Unknown color space: {}", name)),
+    };
+    ret.ok_or_else(|| return savvy_err!("Failed to create color space: {}", name))
 }
 
-/// Takes PNG as input and puts it onto a canvas
-pub fn put_png(
-    input: skia_safe::Data,
-    size: savvy::IntegerSexp,
+/// Reads pixels of an image as RGBA8888 with the specified alpha type
+pub fn read_rgba_pixels(
+    image: &skia_safe::Image,
+    alpha_type: skia_safe::AlphaType,
+) -> anyhow::Result<Vec<u8>, savvy::Error> {
+    let info = skia_safe::ImageInfo::new(
+        image.dimensions(),
+        skia_safe::ColorType::RGBA8888,
+        alpha_type,
+        None,
+    );
+    let row_bytes = info.min_row_bytes();
+    let mut pixels = vec![0u8; row_bytes * image.height() as usize];
+    if !image.read_pixels(
+        &info,
+        pixels.as_mut_slice(),
+        row_bytes,
+        (0, 0),
+        skia_safe::image::CachingHint::Allow,
+    ) {
+        return Err(savvy_err!("Failed to read pixels"));
+    }
+    Ok(pixels)
+}
+
+/// Encodes a skia_safe::Picture into PNG
+pub fn as_png(
+    size: Vec<i32>,
     picture: skia_safe::Picture,
-    left_top: Vec<f64>,
-    props: PaintAttrs,
-) -> anyhow::Result<savvy::OwnedRawSexp, savvy::Error> {
-    let image = skia_safe::Image::from_encoded_with_alpha_type(input, skia_safe::AlphaType::Premul)
-        .ok_or_else(|| return savvy_err!("Failed to read PNG as image"))?;
+) -> anyhow::Result<skia_safe::Data, savvy::Error> {
+    let image = rasterize(size, &picture, None)?;
+    skia_safe::png_encoder::encode_image(None, &image, &skia_safe::png_encoder::Options::default())
+        .ok_or_else(|| return savvy_err!("Failed to encode PNG"))
+}
 
-    let mut recorder = SkiaCanvas::setup(&size)?;
-    let canvas = recorder.start_recording();
-    picture.playback(canvas);
+/// Returns PNG filter flags from their names
+pub fn png_filter_flags(
+    filters: &savvy::StringSexp,
+) -> anyhow::Result<skia_safe::png_encoder::FilterFlag, savvy::Error> {
+    let mut flags = skia_safe::png_encoder::FilterFlag::empty();
+    for f in filters.iter() {
+        flags |= match f {
+            "none" => skia_safe::png_encoder::FilterFlag::NONE,
+            "sub" => skia_safe::png_encoder::FilterFlag::SUB,
+            "up" => skia_safe::png_encoder::FilterFlag::UP,
+            "avg" => skia_safe::png_encoder::FilterFlag::AVG,
+            "paeth" => skia_safe::png_encoder::FilterFlag::PAETH,
+            "all" => skia_safe::png_encoder::FilterFlag::ALL,
+            _ => return Err(savvy_err!("Unknown PNG filter: {}", f)),
+        };
+    }
+    Ok(flags)
+}
+
+/// Returns JPEG chroma subsampling from its name
+pub fn jpeg_downsample(
+    subsampling: &str,
+) -> anyhow::Result<skia_safe::jpeg_encoder::Downsample, savvy::Error> {
+    match subsampling {
+        "420" => Ok(skia_safe::jpeg_encoder::Downsample::BothDirections),
+        "422" => Ok(skia_safe::jpeg_encoder::Downsample::Horizontal),
+        "444" => Ok(skia_safe::jpeg_encoder::Downsample::No),
+        _ => Err(savvy_err!("Unknown chroma subsampling: {}", subsampling)),
+    }
+}
+
+/// Plays a skia_safe::Picture into SVG canvas, returning skia_safe::Data of SVG document
+pub fn as_svg(
+    size: Vec<i32>,
+    picture: skia_safe::Picture,
+    flags: skia_safe::svg::canvas::Flags,
+) -> skia_safe::Data {
+    let canvas = skia_safe::svg::Canvas::new(skia_safe::Rect::from_isize((size[0], size[1])), flags);
+    picture.playback(&canvas);
+    canvas.end()
+}
+
+/// Parses integers of length 7 as skia_safe::pdf::DateTime in UTC
+///
+/// The integers are year, month, day of week, day, hour, minute, and second.
+pub fn as_pdf_datetime(
+    x: &savvy::IntegerSexp,
+) -> anyhow::Result<Option<skia_safe::pdf::DateTime>, savvy::Error> {
+    let x = x.as_slice();
+    match x.len() {
+        0 => return Ok(None),
+        7 => {}
+        n => return Err(savvy_err!("creation must have 0 or 7 elements, but has {}", n)),
+    }
+    // NA is i32::MIN, so it is out of every range
+    let fields = [
+        ("year", 0..=9999),
+        ("month", 1..=12),
+        ("day of week", 0..=6),
+        ("day", 1..=31),
+        ("hour", 0..=23),
+        ("minute", 0..=59),
+        ("second", 0..=60),
+    ];
+    for ((name, range), v) in fields.iter().zip(x) {
+        if !range.contains(v) {
+            return Err(savvy_err!("Invalid {} of creation date: {}", name, v));
+        }
+    }
+    Ok(Some(skia_safe::pdf::DateTime {
+        time_zone_minutes: 0,
+        year: x[0] as u16,
+        month: x[1] as u8,
+        day_of_week: x[2] as u8,
+        day: x[3] as u8,
+        hour: x[4] as u8,
+        minute: x[5] as u8,
+        second: x[6] as u8,
+    }))
+}
+
+/// Plays skia_safe::Picture into PDF document, one picture per page
+///
+/// Each picture is scaled uniformly so that the canvas fits the page,
+/// and centered on the page.
+pub fn as_pdf(
+    size: Vec<i32>,
+    pictures: Vec<skia_safe::Picture>,
+    page_size: (f32, f32),
+    metadata: &skia_safe::pdf::Metadata,
+) -> Vec<u8> {
+    let (width, height) = (size[0] as f32, size[1] as f32);
+    let scale = (page_size.0 / width).min(page_size.1 / height);
+    let offset = (
+        (page_size.0 - width * scale) / 2.0,
+        (page_size.1 - height * scale) / 2.0,
+    );
+    let mut buf: Vec<u8> = Vec::new();
+    {
+        let mut document = skia_safe::pdf::new_document(&mut buf, Some(metadata));
+        for picture in pictures {
+            let mut page = document.begin_page(page_size, None);
+            let canvas = page.canvas();
+            canvas.translate(offset);
+            canvas.scale((scale, scale));
+            picture.playback(canvas);
+            document = page.end_page();
+        }
+        document.close();
+    }
+    buf
+}
+
+/// Decodes encoded image data into a skia_safe::Image
+///
+/// Any format that Skia can decode (PNG, JPEG, WebP, GIF, BMP, ICO, etc.) is accepted.
+pub fn read_image_bytes(bytes: &[u8]) -> anyhow::Result<skia_safe::Image, savvy::Error> {
+    let data = skia_safe::Data::new_copy(bytes);
+    skia_safe::Codec::from_data(data.clone())
+        .ok_or_else(|| return savvy_err!("Unsupported image format or corrupted image data"))?;
+    let image = skia_safe::Image::from_encoded_with_alpha_type(data, skia_safe::AlphaType::Premul)
+        .ok_or_else(|| return savvy_err!("Failed to decode image"))?;
+    Ok(image)
+}
+
+/// Creates a skia_safe::Image from integers of native raster
+///
+/// Each integer is a packed pixel with R in the lowest byte and A in the highest,
+/// that is, the same layout as R's `nativeRaster`.
+pub fn read_native_raster(
+    raster: &savvy::IntegerSexp,
+    width: i32,
+    height: i32,
+    premultiplied: bool,
+) -> anyhow::Result<skia_safe::Image, savvy::Error> {
+    if width <= 0 || height <= 0 || (width as usize) * (height as usize) != raster.len() {
+        return Err(savvy_err!("Native raster does not match its dimensions"));
+    }
+    let pixels: Vec<u8> = raster
+        .iter()
+        .flat_map(|p| (*p as u32).to_le_bytes())
+        .collect();
+    let alpha_type = if premultiplied {
+        skia_safe::AlphaType::Premul
+    } else {
+        skia_safe::AlphaType::Unpremul
+    };
+    let info = skia_safe::ImageInfo::new(
+        (width, height),
+        skia_safe::ColorType::RGBA8888,
+        alpha_type,
+        None,
+    );
+    let pixels = skia_safe::Data::new_copy(&pixels);
+    skia_safe::images::raster_from_data(&info, pixels, width as usize * 4)
+        .ok_or_else(|| return savvy_err!("Failed to create image from native raster"))
+}
+
+/// Returns sampling options from its name
+pub fn sampling_options(name: &str) -> anyhow::Result<skia_safe::SamplingOptions, savvy::Error> {
+    match name {
+        "nearest" => Ok(skia_safe::FilterMode::Nearest.into()),
+        "linear" => Ok(skia_safe::FilterMode::Linear.into()),
+        "cubic" => Ok(skia_safe::CubicResampler::mitchell().into()),
+        _ => Err(savvy_err!("Unknown sampling: {}", name)),
+    }
+}
+
+/// Returns ClipOp from its name
+pub fn clip_op(name: &str) -> anyhow::Result<skia_safe::ClipOp, savvy::Error> {
+    match name {
+        "intersect" => Ok(skia_safe::ClipOp::Intersect),
+        "difference" => Ok(skia_safe::ClipOp::Difference),
+        _ => Err(savvy_err!("Unknown clip op: {}", name)),
+    }
+}
+
+/// Puts an image onto a canvas
+pub fn put_image(
+    image: &skia_safe::Image,
+    canvas: &skia_safe::Canvas,
+    left_top: Vec<f64>,
+    props: &PaintAttrs,
+) {
     canvas.draw_image(
-        &image,
+        image,
         (left_top[0] as f32, left_top[1] as f32),
         Some(&props.paint),
     );
-    let picture = recorder.finish_recording()?;
+}
 
-    Ok(picture)
+/// Returns format, dimensions, and alpha type of encoded image data
+///
+/// @param bytes A raw vector of encoded image.
+/// @returns A list.
+/// @noRd
+#[savvy]
+fn sk_image_info(bytes: RawSexp) -> savvy::Result<savvy::Sexp> {
+    let codec = skia_safe::Codec::from_data(skia_safe::Data::new_copy(bytes.as_slice()))
+        .ok_or_else(|| return savvy_err!("Unsupported image format or corrupted image data"))?;
+    let info = codec.info();
+    let format = format!("{:?}", codec.encoded_format()).to_lowercase();
+    let alpha_type = format!("{:?}", info.alpha_type()).to_lowercase();
+    let color_type = format!("{:?}", info.color_type());
+
+    let format = savvy::OwnedStringSexp::try_from_scalar(&format)?;
+    let width = savvy::OwnedIntegerSexp::try_from_scalar(info.width())?;
+    let height = savvy::OwnedIntegerSexp::try_from_scalar(info.height())?;
+    let alpha_type = savvy::OwnedStringSexp::try_from_scalar(&alpha_type)?;
+    let color_type = savvy::OwnedStringSexp::try_from_scalar(&color_type)?;
+
+    let mut out = savvy::OwnedListSexp::new(5, true)?;
+    out.set_name_and_value(0, "format", format)?;
+    out.set_name_and_value(1, "width", width)?;
+    out.set_name_and_value(2, "height", height)?;
+    out.set_name_and_value(3, "alpha_type", alpha_type)?;
+    out.set_name_and_value(4, "color_type", color_type)?;
+    Ok(out.into())
 }
 
 /// Returns the approximate number of operations in the picture
@@ -0,0 +1,168 @@
+use savvy::{savvy, savvy_err, IntegerSexp, LogicalSexp, NumericScalar, RawSexp};
+
+use crate::canvas::{rasterize, read_image_bytes, read_picture_bytes, read_rgba_pixels};
+
+/// Magic bytes at the beginning of serialized pictures
+const PICTURE_MAGIC: &[u8] = b"skiapict";
+
+/// Reads a serialized picture or encoded image as non-premultiplied RGBA8888 pixels
+///
+/// Pictures are rasterized at `size`, while encoded images keep their own dimensions.
+fn read_pixels_from(
+    bytes: &RawSexp,
+    size: &[i32],
+) -> anyhow::Result<((i32, i32), Vec<u8>), savvy::Error> {
+    let image = if bytes.as_slice().starts_with(PICTURE_MAGIC) {
+        let picture = read_picture_bytes(bytes)?;
+        rasterize(size.to_vec(), &picture, None)?
+    } else {
+        read_image_bytes(bytes.as_slice())?
+    };
+    let pixels = read_rgba_pixels(&image, skia_safe::AlphaType::Unpremul)?;
+    Ok(((image.width(), image.height()), pixels))
+}
+
+/// Per-channel difference statistics of two images
+struct DiffStats {
+    max: [i32; 4],
+    mean: [f64; 4],
+    psnr: f64,
+    n_diff: i32,
+}
+
+/// Compares two buffers of RGBA8888 pixels
+///
+/// A pixel is counted as different when the difference of any channel exceeds `threshold`.
+/// If `diff` is `true`, also returns pixels of an image
+/// where differing pixels are painted red over a faded grayscale of `x`.
+fn compare_pixels(x: &[u8], y: &[u8], threshold: i32, diff: bool) -> (DiffStats, Option<Vec<u8>>) {
+    let mut max = [0i32; 4];
+    let mut sum = [0f64; 4];
+    let mut sq_sum = 0f64;
+    let mut n_diff = 0;
+    let mut out = if diff {
+        Some(Vec::with_capacity(x.len()))
+    } else {
+        None
+    };
+
+    for (px, py) in x.chunks(4).zip(y.chunks(4)) {
+        let mut changed = false;
+        for c in 0..4 {
+            let d = (px[c] as i32 - py[c] as i32).abs();
+            max[c] = max[c].max(d);
+            sum[c] += d as f64;
+            sq_sum += (d * d) as f64;
+            changed |= d > threshold;
+        }
+        if changed {
+            n_diff += 1;
+        }
+        if let Some(out) = out.as_mut() {
+            if changed {
+                out.extend_from_slice(&[255, 0, 0, 255]);
+            } else {
+                let luma = 0.299 * px[0] as f64 + 0.587 * px[1] as f64 + 0.114 * px[2] as f64;
+                // Blends the luma over white by the alpha, then fades it
+                let gray = 255.0 - (255.0 - luma) * (px[3] as f64 / 255.0);
+                let gray = (255.0 - (255.0 - gray) / 4.0).round() as u8;
+                out.extend_from_slice(&[gray, gray, gray, 255]);
+            }
+        }
+    }
+    let n = (x.len() / 4).max(1) as f64;
+    let mse = sq_sum / (n * 4.0);
+    let psnr = if mse == 0.0 {
+        f64::INFINITY
+    } else {
+        10.0 * (255.0 * 255.0 / mse).log10()
+    };
+    let stats = DiffStats {
+        max,
+        mean: sum.map(|s| s / n),
+        psnr,
+        n_diff,
+    };
+    (stats, out)
+}
+
+/// Compares two pictures or encoded images pixel by pixel
+///
+/// @param size Canvas size used to rasterize pictures.
+/// @param x,y Raw vectors of serialized pictures or encoded images.
+/// @param threshold Pixels are counted as different when any channel differs more than this.
+/// @param diff Whether to return a PNG image highlighting differing pixels.
+/// @returns A list.
+/// @noRd
+#[savvy]
+fn sk_image_diff(
+    size: IntegerSexp,
+    x: RawSexp,
+    y: RawSexp,
+    threshold: NumericScalar,
+    diff: LogicalSexp,
+) -> savvy::Result<savvy::Sexp> {
+    let ((width, height), px) = read_pixels_from(&x, size.as_slice())?;
+    let ((y_width, y_height), py) = read_pixels_from(&y, size.as_slice())?;
+    if (width, height) != (y_width, y_height) {
+        return Err(savvy_err!(
+            "Images must have the same dimensions, but got {}x{} and {}x{}",
+            width,
+            height,
+            y_width,
+            y_height
+        ));
+    }
+
+    let (stats, diff_pixels) =
+        compare_pixels(&px, &py, threshold.as_i32()?.max(0), diff.to_vec()[0]);
+
+    let mut out = savvy::OwnedListSexp::new(5, true)?;
+    out.set_name_and_value(
+        0,
+        "max",
+        savvy::OwnedIntegerSexp::try_from_slice(stats.max)?,
+    )?;
+    out.set_name_and_value(1, "mean", savvy::OwnedRealSexp::try_from_slice(stats.mean)?)?;
+    out.set_name_and_value(
+        2,
+        "psnr",
+        savvy::OwnedRealSexp::try_from_scalar(stats.psnr)?,
+    )?;
+    out.set_name_and_value(
+        3,
+        "n_diff",
+        savvy::OwnedIntegerSexp::try_from_scalar(stats.n_diff)?,
+    )?;
+    match diff_pixels {
+        Some(pixels) => {
+            let info = skia_safe::ImageInfo::new(
+                (width, height),
+                skia_safe::ColorType::RGBA8888,
+                skia_safe::AlphaType::Unpremul,
+                None,
+            );
+            let image = skia_safe::images::raster_from_data(
+                &info,
+                skia_safe::Data::new_copy(&pixels),
+                width as usize * 4,
+            )
+            .ok_or_else(|| return savvy_err!("Failed to create diff image"))?;
+            let data = skia_safe::png_encoder::encode_image(
+                None,
+                &image,
+                &skia_safe::png_encoder::Options::default(),
+            )
+            .ok_or_else(|| return savvy_err!("Failed to encode PNG"))?;
+            out.set_name_and_value(
+                4,
+                "diff",
+                savvy::OwnedRawSexp::try_from_slice(data.as_bytes())?,
+            )?;
+        }
+        None => {
+            out.set_name_and_value(4, "diff", savvy::NullSexp)?;
+        }
+    }
+    Ok(out.into())
+}
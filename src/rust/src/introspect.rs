@@ -0,0 +1,555 @@
+use savvy::{savvy, savvy_err, RawSexp};
+
+use crate::canvas::read_picture_bytes;
+
+/// Names of drawing operations in serialized pictures
+///
+/// Indexed by the op codes of `DrawType` in Skia's `SkPictureFlat.h`.
+/// Retired op codes are never written by recent versions of Skia.
+const OP_NAMES: [&str; 77] = [
+    "unused",
+    "clip_path",
+    "clip_region",
+    "clip_rect",
+    "clip_rrect",
+    "concat",
+    "draw_bitmap",
+    "draw_bitmap_matrix",
+    "draw_bitmap_nine",
+    "draw_bitmap_rect",
+    "draw_clear",
+    "draw_data",
+    "draw_oval",
+    "draw_paint",
+    "draw_path",
+    "draw_picture",
+    "draw_points",
+    "draw_pos_text",
+    "draw_pos_text_top_bottom",
+    "draw_pos_text_h",
+    "draw_pos_text_h_top_bottom",
+    "draw_rect",
+    "draw_rrect",
+    "draw_sprite",
+    "draw_text",
+    "draw_text_on_path",
+    "draw_text_top_bottom",
+    "draw_vertices",
+    "restore",
+    "rotate",
+    "save",
+    "save_layer_saveflags",
+    "scale",
+    "set_matrix",
+    "skew",
+    "translate",
+    "noop",
+    "begin_comment_group",
+    "comment",
+    "end_comment_group",
+    "draw_drrect",
+    "push_cull",
+    "pop_cull",
+    "draw_patch",
+    "draw_picture_matrix_paint",
+    "draw_text_blob",
+    "draw_image",
+    "draw_image_rect_strict",
+    "draw_atlas",
+    "draw_image_nine",
+    "draw_image_rect",
+    "save_layer_savelayerflags",
+    "save_layer",
+    "draw_annotation",
+    "draw_drawable",
+    "draw_drawable_matrix",
+    "draw_text_rsxform",
+    "translate_z",
+    "draw_shadow_rec",
+    "draw_image_lattice",
+    "draw_arc",
+    "draw_region",
+    "draw_vertices_object",
+    "flush",
+    "draw_edgeaa_image_set",
+    "save_behind",
+    "draw_edgeaa_quad",
+    "draw_behind_paint",
+    "concat44",
+    "clip_shader_in_paint",
+    "mark_ctm",
+    "set_m44",
+    "draw_image2",
+    "draw_image_rect2",
+    "draw_image_lattice2",
+    "draw_edgeaa_image_set2",
+    "reset_clip",
+];
+
+/// Magic bytes at the start of serialized pictures
+const MAGIC: &[u8; 8] = b"skiapict";
+
+/// Oldest version of serialized pictures that can be listed
+///
+/// Since version 87 of `SkPicturePriv.h`,
+/// paints are flattened with a blender and without a draw looper.
+const MIN_VERSION: u32 = 87;
+
+/// Newest version of serialized pictures that can be listed
+///
+/// This is the layout that `OP_NAMES`, `read_paint()`, and `skip_typeface()` implement.
+/// Skia does not let custom canvases be played back into from Rust,
+/// so ops are read from the private format instead,
+/// and this must only be raised after checking them against Skia's sources
+/// when skia-safe is updated.
+const MAX_SUPPORTED_VERSION: u32 = 110;
+
+/// Tags of chunks in serialized pictures ('read', 'fact', 'tpfc', 'aray', and 'pnt ')
+const READER_TAG: u32 = u32::from_be_bytes(*b"read");
+const FACTORY_TAG: u32 = u32::from_be_bytes(*b"fact");
+const TYPEFACE_TAG: u32 = u32::from_be_bytes(*b"tpfc");
+const BUFFER_SIZE_TAG: u32 = u32::from_be_bytes(*b"aray");
+const PAINT_BUFFER_TAG: u32 = u32::from_be_bytes(*b"pnt ");
+
+/// Byte size of the header of serialized pictures (magic, version, cull rect, and a flag)
+const HEADER_SIZE: usize = 8 + 4 + 16 + 1;
+
+/// Names of `SkPaint::Style`, the same as those of `Style`
+const STYLE_NAMES: [&str; 3] = ["Fill", "Stroke", "StrokeAndFill"];
+
+/// Names of `SkBlendMode`, the same as those of `BlendMode`
+const BLEND_MODE_NAMES: [&str; 29] = [
+    "Clear",
+    "Src",
+    "Dst",
+    "SrcOver",
+    "DstOver",
+    "SrcIn",
+    "DstIn",
+    "SrcOut",
+    "DstOut",
+    "SrcATop",
+    "DstATop",
+    "Xor",
+    "Plus",
+    "Modulate",
+    "Screen",
+    "Overlay",
+    "Darken",
+    "Lighten",
+    "ColorDodge",
+    "ColorBurn",
+    "HardLight",
+    "SoftLight",
+    "Difference",
+    "Exclusion",
+    "Multiply",
+    "Hue",
+    "Saturation",
+    "Color",
+    "Luminosity",
+];
+
+/// Attributes of a paint stored in a serialized picture
+struct PaintInfo {
+    color: [f32; 4],
+    style: &'static str,
+    blend_mode: &'static str,
+}
+
+/// A drawing operation listed from a serialized picture
+struct PictureOp {
+    name: &'static str,
+    offset: usize,
+    size: usize,
+    depth: i32,
+    paint: Option<u32>,
+    bounds: Option<[f32; 4]>,
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    bytes
+        .get(at..at + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn read_rect(bytes: &[u8], at: usize) -> Option<[f32; 4]> {
+    let mut rect = [0f32; 4];
+    for (i, v) in rect.iter_mut().enumerate() {
+        *v = f32::from_bits(read_u32(bytes, at + i * 4)?);
+    }
+    Some(rect)
+}
+
+/// Cursor over the data that follows the op stream of a serialized picture
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn skip(&mut self, n: usize) -> Option<()> {
+        let end = self.pos.checked_add(n)?;
+        if end > self.bytes.len() {
+            return None;
+        }
+        self.pos = end;
+        Some(())
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let v = read_u32(self.bytes, self.pos)?;
+        self.pos += 4;
+        Some(v)
+    }
+
+    fn f32(&mut self) -> Option<f32> {
+        self.u32().map(f32::from_bits)
+    }
+
+    /// Reads an integer written by `SkWStream::writePackedUInt()`
+    fn packed(&mut self) -> Option<u32> {
+        let first = *self.bytes.get(self.pos)?;
+        self.pos += 1;
+        match first {
+            0xFE => {
+                let b = self.bytes.get(self.pos..self.pos + 2)?;
+                self.pos += 2;
+                Some(u16::from_le_bytes([b[0], b[1]]) as u32)
+            }
+            0xFF => self.u32(),
+            _ => Some(first as u32),
+        }
+    }
+}
+
+/// Skips a typeface written by `SkFontDescriptor::serialize()`
+fn skip_typeface(r: &mut Reader) -> Option<()> {
+    // Style bits
+    r.packed()?;
+    loop {
+        match r.packed()? {
+            // Family name, full name, and PostScript name
+            0x01 | 0x04 | 0x06 => {
+                let len = r.packed()?;
+                r.skip(len as usize)?;
+            }
+            // Weight, width, slant, and italic
+            0x10..=0x13 => r.skip(4)?,
+            // Palette index, factory id, and collection index
+            0xF8 | 0xFC | 0xFD => {
+                r.packed()?;
+            }
+            // Palette entry overrides
+            0xF9 => {
+                for _ in 0..r.packed()? {
+                    r.packed()?;
+                    r.skip(4)?;
+                }
+            }
+            // Variation coordinates
+            0xFA => {
+                let count = r.packed()?;
+                r.skip(count as usize * 8)?;
+            }
+            0xFF => break,
+            _ => return None,
+        }
+    }
+    // Font data
+    let len = r.packed()?;
+    r.skip(len as usize)
+}
+
+/// Skips an effect written by `SkBinaryWriteBuffer::writeFlattenable()`
+fn skip_flattenable(r: &mut Reader, has_factories: bool) -> Option<()> {
+    let head = r.u32()?;
+    if head == 0 {
+        return Some(());
+    }
+    // Without a factory table, the type name is written as a string
+    // unless it has been written before
+    if !has_factories && head & 0xFF != 0 {
+        r.skip((head as usize + 1).next_multiple_of(4))?;
+    }
+    let size = r.u32()?;
+    r.skip(size as usize)
+}
+
+/// Reads a paint written by `SkPaintPriv::Flatten()`
+fn read_paint(r: &mut Reader, has_factories: bool) -> Option<PaintInfo> {
+    // Stroke width and stroke miter
+    r.skip(8)?;
+    let color = [r.f32()?, r.f32()?, r.f32()?, r.f32()?];
+    let packed = r.u32()?;
+    // 0xFF for a custom blender
+    let blend_mode = BLEND_MODE_NAMES
+        .get(((packed >> 8) & 0xFF) as usize)
+        .copied()
+        .unwrap_or("Custom");
+    let style = STYLE_NAMES.get(((packed >> 20) & 0x3) as usize)?;
+    // Path effect, shader, mask filter, color filter, image filter, and blender
+    if (packed >> 24) & 0x2 != 0 {
+        for _ in 0..6 {
+            skip_flattenable(r, has_factories)?;
+        }
+    }
+    Some(PaintInfo {
+        color,
+        style,
+        blend_mode,
+    })
+}
+
+/// Reads the table of paints from the data that follows the op stream
+///
+/// Factories and typefaces come first, and then the buffer starting with paints.
+fn read_paints(bytes: &[u8]) -> Option<Vec<PaintInfo>> {
+    let mut r = Reader { bytes, pos: 0 };
+    let mut has_factories = false;
+    loop {
+        let tag = r.u32()?;
+        let size = r.u32()?;
+        match tag {
+            FACTORY_TAG => {
+                has_factories = true;
+                r.skip(size as usize)?;
+            }
+            // The size is the number of typefaces
+            TYPEFACE_TAG => {
+                for _ in 0..size {
+                    skip_typeface(&mut r)?;
+                }
+            }
+            BUFFER_SIZE_TAG => break,
+            _ => return None,
+        }
+    }
+    let mut ret = Vec::new();
+    if read_u32(r.bytes, r.pos) == Some(PAINT_BUFFER_TAG) {
+        r.skip(4)?;
+        let count = r.u32()?;
+        for _ in 0..count {
+            ret.push(read_paint(&mut r, has_factories)?);
+        }
+    }
+    Some(ret)
+}
+
+/// Checks the magic bytes and the version of a serialized picture
+///
+/// Pictures of versions out of `MIN_VERSION..=MAX_SUPPORTED_VERSION`
+/// are rejected rather than misread.
+fn check_version(bytes: &[u8]) -> anyhow::Result<(), savvy::Error> {
+    if bytes.len() < HEADER_SIZE || &bytes[..8] != MAGIC {
+        return Err(savvy_err!("Not a serialized picture"));
+    }
+    let version = read_u32(bytes, 8).unwrap_or(0);
+    if !(MIN_VERSION..=MAX_SUPPORTED_VERSION).contains(&version) {
+        return Err(savvy_err!(
+            "Unsupported picture version {} (supported versions are {} to {})",
+            version,
+            MIN_VERSION,
+            MAX_SUPPORTED_VERSION
+        ));
+    }
+    Ok(())
+}
+
+/// Lists drawing operations by walking the op stream of a serialized picture
+///
+/// Only the op type and its byte size are read for every operation.
+/// The paint index (1-based) and bounds are read for operations
+/// whose arguments start with them.
+/// Paints referred to by the indices are read from the table of paints.
+fn list_ops(bytes: &[u8]) -> anyhow::Result<(Vec<PictureOp>, Vec<PaintInfo>), savvy::Error> {
+    check_version(bytes)?;
+    if bytes.len() == HEADER_SIZE || bytes[HEADER_SIZE - 1] == 0 {
+        return Ok((Vec::new(), Vec::new()));
+    }
+    let tag = read_u32(bytes, HEADER_SIZE);
+    let len = read_u32(bytes, HEADER_SIZE + 4);
+    let (Some(READER_TAG), Some(len)) = (tag, len) else {
+        return Err(savvy_err!("Unsupported picture format"));
+    };
+    let start = HEADER_SIZE + 8;
+    let end = start + len as usize;
+    let ops = bytes
+        .get(start..end)
+        .ok_or_else(|| return savvy_err!("Picture data is truncated"))?;
+    let paints =
+        read_paints(&bytes[end..]).ok_or_else(|| return savvy_err!("Failed to read paints"))?;
+
+    let mut ret = Vec::new();
+    let mut depth = 0;
+    let mut pos = 0;
+    while pos < ops.len() {
+        let packed =
+            read_u32(ops, pos).ok_or_else(|| return savvy_err!("Picture data is truncated"))?;
+        let code = (packed >> 24) as usize;
+        let mut size = (packed & 0x00FF_FFFF) as usize;
+        let mut args = pos + 4;
+        if size == 0x00FF_FFFF {
+            size = read_u32(ops, args)
+                .ok_or_else(|| return savvy_err!("Picture data is truncated"))?
+                as usize;
+            args += 4;
+        }
+        if code == 0 || code >= OP_NAMES.len() || size < 4 || pos + size > ops.len() {
+            return Err(savvy_err!("Failed to read operation at offset {}", pos));
+        }
+        let name = OP_NAMES[code];
+
+        let (paint, bounds) = match name {
+            "draw_rect" | "draw_oval" | "draw_rrect" | "draw_arc" | "draw_drrect" => {
+                (read_u32(ops, args), read_rect(ops, args + 4))
+            }
+            "draw_image_rect2" => (read_u32(ops, args), read_rect(ops, args + 8 + 16)),
+            "clip_rect" | "clip_rrect" => (None, read_rect(ops, args)),
+            "draw_paint"
+            | "draw_path"
+            | "draw_points"
+            | "draw_region"
+            | "draw_text_blob"
+            | "draw_vertices_object"
+            | "draw_atlas"
+            | "draw_image2"
+            | "draw_patch"
+            | "draw_picture_matrix_paint"
+            | "draw_behind_paint" => (read_u32(ops, args), None),
+            "save_layer" => {
+                // Flags: 1 for bounds and 2 for paint
+                let flags = read_u32(ops, args).unwrap_or(0);
+                let bounds = if flags & 1 != 0 {
+                    read_rect(ops, args + 4)
+                } else {
+                    None
+                };
+                let at = args + 4 + if flags & 1 != 0 { 16 } else { 0 };
+                let paint = if flags & 2 != 0 {
+                    read_u32(ops, at)
+                } else {
+                    None
+                };
+                (paint, bounds)
+            }
+            _ => (None, None),
+        };
+        if name == "restore" {
+            depth -= 1;
+        }
+        ret.push(PictureOp {
+            name,
+            offset: pos,
+            size,
+            depth,
+            paint: paint.filter(|p| *p > 0),
+            bounds,
+        });
+        if matches!(name, "save" | "save_layer" | "save_behind") {
+            depth += 1;
+        }
+        pos += size;
+    }
+    Ok((ret, paints))
+}
+
+/// Returns cull rect, memory usage, and number of operations of a picture
+///
+/// @param picture A raw vector of picture.
+/// @returns A list.
+/// @noRd
+#[savvy]
+fn sk_picture_info(picture: RawSexp) -> savvy::Result<savvy::Sexp> {
+    let bytes = picture.as_slice().len();
+    let picture = read_picture_bytes(&picture)?;
+    let cull = picture.cull_rect();
+
+    let cull = savvy::OwnedRealSexp::try_from_slice([
+        cull.left as f64,
+        cull.top as f64,
+        cull.right as f64,
+        cull.bottom as f64,
+    ])?;
+    let op_count = savvy::OwnedIntegerSexp::try_from_scalar(picture.approximate_op_count() as i32)?;
+    let op_count_nested =
+        savvy::OwnedIntegerSexp::try_from_scalar(picture.approximate_op_count_nested(true) as i32)?;
+    let bytes_used =
+        savvy::OwnedRealSexp::try_from_scalar(picture.approximate_bytes_used() as f64)?;
+    let serialized_size = savvy::OwnedRealSexp::try_from_scalar(bytes as f64)?;
+
+    let mut out = savvy::OwnedListSexp::new(5, true)?;
+    out.set_name_and_value(0, "cull_rect", cull)?;
+    out.set_name_and_value(1, "op_count", op_count)?;
+    out.set_name_and_value(2, "op_count_nested", op_count_nested)?;
+    out.set_name_and_value(3, "bytes_used", bytes_used)?;
+    out.set_name_and_value(4, "serialized_size", serialized_size)?;
+    Ok(out.into())
+}
+
+/// Formats a color as a hex string of RGBA
+fn color_hex(color: [f32; 4]) -> String {
+    let [r, g, b, a] = color.map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8);
+    format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, a)
+}
+
+/// Lists drawing operations recorded in a picture
+///
+/// @param picture A raw vector of picture.
+/// @returns A list of columns.
+/// @noRd
+#[savvy]
+fn sk_picture_ops(picture: RawSexp) -> savvy::Result<savvy::Sexp> {
+    // Fails early for bytes that are not a picture at all
+    read_picture_bytes(&picture)?;
+    let (ops, paints) = list_ops(picture.as_slice())?;
+
+    let n = ops.len();
+    let mut name = savvy::OwnedStringSexp::new(n)?;
+    let mut offset = savvy::OwnedRealSexp::new(n)?;
+    let mut size = savvy::OwnedRealSexp::new(n)?;
+    let mut depth = savvy::OwnedIntegerSexp::new(n)?;
+    let mut color = savvy::OwnedStringSexp::new(n)?;
+    let mut style = savvy::OwnedStringSexp::new(n)?;
+    let mut blend_mode = savvy::OwnedStringSexp::new(n)?;
+    let mut ltrb: Vec<savvy::OwnedRealSexp> = (0..4)
+        .map(|_| savvy::OwnedRealSexp::new(n))
+        .collect::<savvy::Result<_>>()?;
+    for (i, op) in ops.iter().enumerate() {
+        name.set_elt(i, op.name)?;
+        offset.set_elt(i, op.offset as f64)?;
+        size.set_elt(i, op.size as f64)?;
+        depth.set_elt(i, op.depth)?;
+        match op.paint.and_then(|p| paints.get(p as usize - 1)) {
+            Some(p) => {
+                color.set_elt(i, &color_hex(p.color))?;
+                style.set_elt(i, p.style)?;
+                blend_mode.set_elt(i, p.blend_mode)?;
+            }
+            None => {
+                color.set_na(i)?;
+                style.set_na(i)?;
+                blend_mode.set_na(i)?;
+            }
+        }
+        for (j, col) in ltrb.iter_mut().enumerate() {
+            match op.bounds {
+                Some(b) => col.set_elt(i, b[j] as f64)?,
+                None => col.set_na(i)?,
+            }
+        }
+    }
+
+    let mut out = savvy::OwnedListSexp::new(11, true)?;
+    out.set_name_and_value(0, "op", name)?;
+    out.set_name_and_value(1, "offset", offset)?;
+    out.set_name_and_value(2, "size", size)?;
+    out.set_name_and_value(3, "depth", depth)?;
+    out.set_name_and_value(4, "color", color)?;
+    out.set_name_and_value(5, "style", style)?;
+    out.set_name_and_value(6, "blend_mode", blend_mode)?;
+    for (j, (key, col)) in ["l", "t", "r", "b"].iter().zip(ltrb).enumerate() {
+        out.set_name_and_value(7 + j, key, col)?;
+    }
+    Ok(out.into())
+}
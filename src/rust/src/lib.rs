@@ -1,52 +1,59 @@
+mod animation;
 mod canvas;
+mod diff;
+mod introspect;
 mod paint_attrs;
+mod paragraph;
 mod path_transform;
 mod runtime_effect;
+mod text_path;
+mod tiled;
 
 use canvas::{read_picture_bytes, SkiaCanvas};
-use paint_attrs::{assert_len, PaintAttrs};
+use paint_attrs::{assert_len, recycle, recycled_len, PaintAttrs};
 
 use savvy::{savvy, savvy_err, IntegerSexp, LogicalSexp, NumericScalar, NumericSexp, StringSexp};
-use skia_safe::{Data, Image};
 
 /// Takes a raw vector of picture and returns a native raster
 ///
 /// @param size Canvas size.
 /// @param curr_bytes Current canvas state.
+/// @param premultiplied Whether to return colors premultiplied by alpha.
+/// @param scale Device scale factor.
 /// @returns An integer matrix that represents a native raster.
 /// @noRd
 #[savvy]
-fn sk_as_nativeraster(size: IntegerSexp, curr_bytes: savvy::RawSexp) -> savvy::Result<savvy::Sexp> {
-    assert_len("size", 2, size.len())?;
+fn sk_as_nativeraster(
+    size: IntegerSexp,
+    curr_bytes: savvy::RawSexp,
+    premultiplied: LogicalSexp,
+    scale: NumericScalar,
+) -> savvy::Result<savvy::Sexp> {
+    canvas::validate_size(size.as_slice())?;
 
     let picture = read_picture_bytes(&curr_bytes)?;
     let size = size.to_vec();
-
-    let mut surface = skia_safe::surfaces::raster_n32_premul((size[0], size[1]))
-        .unwrap_or_else(|| skia_safe::surfaces::raster_n32_premul((768, 576)).unwrap());
-    picture.playback(surface.canvas());
-
-    let image = surface.image_snapshot();
-
-    // NOTE: Here we assume the ColorType is BGRA8888 with premultiplied alpha.
-    let pixmap = image
-        .peek_pixels()
-        .ok_or_else(|| return savvy_err!("Failed to peek pixels."))?;
-
-    let pixels = pixmap
-        .bytes()
-        .ok_or_else(|| return savvy_err!("Failed to read pixel bytes"))?;
-
+    let alpha_type = if premultiplied.to_vec()[0] {
+        skia_safe::AlphaType::Premul
+    } else {
+        skia_safe::AlphaType::Unpremul
+    };
+
+    let image = canvas::rasterize_with(
+        size,
+        &picture,
+        &canvas::RasterOptions {
+            scale: scale.as_f64(),
+            ..Default::default()
+        },
+    )?;
+    let pixels = canvas::read_rgba_pixels(&image, alpha_type)?;
+
+    // Pixels are packed in the same way as R's nativeRaster:
+    // R in the lowest byte and A in the highest byte.
     let data: Vec<i32> = pixels
         .chunks(4)
-        .map(|p| {
-            let b = p[0] as u32;
-            let g = p[1] as u32;
-            let r = p[2] as u32;
-            let a = p[3] as u32;
-            let col: u32 = (a << 24) | (b << 16) | (g << 8) | r;
-            col as i32
-        })
+        .map(|p| u32::from_le_bytes([p[0], p[1], p[2], p[3]]) as i32)
         .collect();
 
     let mut out = savvy::OwnedIntegerSexp::try_from_slice(data.as_slice())?;
@@ -56,99 +63,998 @@ fn sk_as_nativeraster(size: IntegerSexp, curr_bytes: savvy::RawSexp) -> savvy::R
     Ok(out.into())
 }
 
+/// Takes a raw vector of picture and returns an RGBA array
+///
+/// @param size Canvas size.
+/// @param curr_bytes Current canvas state.
+/// @param scale Device scale factor.
+/// @returns A numeric array of height x width x 4 with unpremultiplied colors in `[0, 1]`.
+/// @noRd
+#[savvy]
+fn sk_as_rgba_array(
+    size: IntegerSexp,
+    curr_bytes: savvy::RawSexp,
+    scale: NumericScalar,
+) -> savvy::Result<savvy::Sexp> {
+    canvas::validate_size(size.as_slice())?;
+
+    let picture = read_picture_bytes(&curr_bytes)?;
+    let size = size.to_vec();
+
+    let image = canvas::rasterize_with(
+        size,
+        &picture,
+        &canvas::RasterOptions {
+            scale: scale.as_f64(),
+            ..Default::default()
+        },
+    )?;
+    let pixels = canvas::read_rgba_pixels(&image, skia_safe::AlphaType::Unpremul)?;
+
+    // R arrays are column-major, so that the index of [y, x, channel] is
+    // y + x * height + channel * height * width.
+    let height = image.height() as usize;
+    let width = image.width() as usize;
+    let mut out = savvy::OwnedRealSexp::new(height * width * 4)?;
+    for (i, p) in pixels.chunks(4).enumerate() {
+        let (y, x) = (i / width, i % width);
+        for (c, v) in p.iter().enumerate() {
+            out.set_elt(y + x * height + c * height * width, *v as f64 / 255.0)?;
+        }
+    }
+    out.set_dim(vec![height as i32, width as i32, 4].as_slice())?;
+
+    Ok(out.into())
+}
+
 /// Takes a raw vector of picture and returns PNG data
 ///
 /// @param size Canvas size.
 /// @param curr_bytes Current canvas state.
+/// @param compression zlib compression level (0-9).
+/// @param filters Names of PNG filters to use.
+/// @param scale Device scale factor.
+/// @param color_space Name of the working color space.
+/// The PNG data is tagged with the matching ICC profile.
+/// If empty, the picture is rendered without a color space and the PNG data is not tagged.
+/// @param f16 Whether to render into RGBA F16 and encode as 16-bit PNG.
 /// @returns A raw vector of PNG data.
 /// @noRd
 #[savvy]
-fn sk_as_png(size: IntegerSexp, curr_bytes: savvy::RawSexp) -> savvy::Result<savvy::Sexp> {
-    assert_len("size", 2, size.len())?;
+fn sk_as_png(
+    size: IntegerSexp,
+    curr_bytes: savvy::RawSexp,
+    compression: NumericScalar,
+    filters: StringSexp,
+    scale: NumericScalar,
+    color_space: StringSexp,
+    f16: LogicalSexp,
+) -> savvy::Result<savvy::Sexp> {
+    canvas::validate_size(size.as_slice())?;
+
+    let picture = read_picture_bytes(&curr_bytes)?;
+    let size = size.to_vec();
+
+    let mut options = skia_safe::png_encoder::Options::default();
+    options.z_lib_level = compression.as_i32()?.clamp(0, 9);
+    options.filter_flags = canvas::png_filter_flags(&filters)?;
+
+    let color_space = match color_space.len() {
+        0 => None,
+        _ => Some(canvas::color_space(color_space.to_vec()[0])?),
+    };
+    let image = canvas::rasterize_with(
+        size,
+        &picture,
+        &canvas::RasterOptions {
+            scale: scale.as_f64(),
+            color_space,
+            f16: f16.to_vec()[0],
+            ..Default::default()
+        },
+    )?;
+    let data = skia_safe::png_encoder::encode_image(None, &image, &options)
+        .ok_or_else(|| return savvy_err!("Failed to encode PNG"))?;
+
+    let ret = savvy::OwnedRawSexp::try_from_slice(data.as_bytes())?;
+    Ok(ret.into())
+}
+
+/// Takes a raw vector of picture and returns JPEG data
+///
+/// @param size Canvas size.
+/// @param curr_bytes Current canvas state.
+/// @param quality Encoding quality (0-100).
+/// @param subsampling Chroma subsampling. One of "420", "422", or "444".
+/// @param background Background color to blend transparent pixels with.
+/// @param scale Device scale factor.
+/// @returns A raw vector of JPEG data.
+/// @noRd
+#[savvy]
+fn sk_as_jpeg(
+    size: IntegerSexp,
+    curr_bytes: savvy::RawSexp,
+    quality: NumericScalar,
+    subsampling: StringSexp,
+    background: NumericSexp,
+    scale: NumericScalar,
+) -> savvy::Result<savvy::Sexp> {
+    canvas::validate_size(size.as_slice())?;
+
+    let picture = read_picture_bytes(&curr_bytes)?;
+    let size = size.to_vec();
+    let background = paint_attrs::num2colors(&background)
+        .ok_or_else(|| return savvy_err!("Failed to parse color"))?;
+
+    let options = skia_safe::jpeg_encoder::Options {
+        quality: quality.as_i32()?.clamp(0, 100) as u32,
+        downsample: canvas::jpeg_downsample(subsampling.to_vec()[0])?,
+        ..Default::default()
+    };
+
+    let image = canvas::rasterize_with(
+        size,
+        &picture,
+        &canvas::RasterOptions {
+            background: Some(background[0]),
+            scale: scale.as_f64(),
+            ..Default::default()
+        },
+    )?;
+    let data = skia_safe::jpeg_encoder::encode_image(None, &image, &options)
+        .ok_or_else(|| return savvy_err!("Failed to encode JPEG"))?;
+
+    let ret = savvy::OwnedRawSexp::try_from_slice(data.as_bytes())?;
+    Ok(ret.into())
+}
+
+/// Takes a raw vector of picture and returns WebP data
+///
+/// @param size Canvas size.
+/// @param curr_bytes Current canvas state.
+/// @param quality Encoding quality (0-100).
+/// For lossless compression, this controls the effort to reduce the size.
+/// @param lossless Whether to use lossless compression.
+/// @param scale Device scale factor.
+/// @returns A raw vector of WebP data.
+/// @noRd
+#[savvy]
+fn sk_as_webp(
+    size: IntegerSexp,
+    curr_bytes: savvy::RawSexp,
+    quality: NumericScalar,
+    lossless: LogicalSexp,
+    scale: NumericScalar,
+) -> savvy::Result<savvy::Sexp> {
+    canvas::validate_size(size.as_slice())?;
+
+    let picture = read_picture_bytes(&curr_bytes)?;
+    let size = size.to_vec();
+
+    let options = skia_safe::webp_encoder::Options {
+        compression: if lossless.to_vec()[0] {
+            skia_safe::webp_encoder::Compression::Lossless
+        } else {
+            skia_safe::webp_encoder::Compression::Lossy
+        },
+        quality: quality.as_f64().clamp(0.0, 100.0) as f32,
+    };
+
+    let image = canvas::rasterize_with(
+        size,
+        &picture,
+        &canvas::RasterOptions {
+            scale: scale.as_f64(),
+            ..Default::default()
+        },
+    )?;
+    let data = skia_safe::webp_encoder::encode_image(None, &image, &options)
+        .ok_or_else(|| return savvy_err!("Failed to encode WebP"))?;
+
+    let ret = savvy::OwnedRawSexp::try_from_slice(data.as_bytes())?;
+    Ok(ret.into())
+}
+
+/// Takes a raw vector of picture and returns SVG document
+///
+/// @param size Canvas size.
+/// @param curr_bytes Current canvas state.
+/// @param text_to_path Whether to convert text into paths.
+/// @param pretty Whether to pretty-print XML.
+/// @returns A string of SVG document.
+/// @noRd
+#[savvy]
+fn sk_as_svg(
+    size: IntegerSexp,
+    curr_bytes: savvy::RawSexp,
+    text_to_path: LogicalSexp,
+    pretty: LogicalSexp,
+) -> savvy::Result<savvy::Sexp> {
+    canvas::validate_size(size.as_slice())?;
 
     let picture = read_picture_bytes(&curr_bytes)?;
     let size = size.to_vec();
 
-    let data = canvas::as_png(size, picture).ok_or_else(|| return savvy_err!("Failed to encode PNG"))?;
+    let mut flags = skia_safe::svg::canvas::Flags::empty();
+    if text_to_path.to_vec()[0] {
+        flags |= skia_safe::svg::canvas::Flags::CONVERT_TEXT_TO_PATHS;
+    }
+    if !pretty.to_vec()[0] {
+        flags |= skia_safe::svg::canvas::Flags::NO_PRETTY_XML;
+    }
+    let data = canvas::as_svg(size, picture, flags);
+    let svg = std::str::from_utf8(data.as_bytes())
+        .map_err(|e| savvy_err!("Failed to read SVG as UTF-8 string:\n {}", e))?;
+
+    let ret = savvy::OwnedStringSexp::try_from_scalar(svg)?;
+    Ok(ret.into())
+}
+
+/// Takes a list of pictures and returns PDF document
+///
+/// @param size Canvas size.
+/// @param pictures A list of raw vectors of picture. Each picture is drawn on a page.
+/// @param page_size Page size in points.
+/// @param metadata Title, author, subject, and keywords of the document.
+/// @param creation Creation date. Integers of length 7 or an empty vector.
+/// @returns A raw vector of PDF data.
+/// @noRd
+#[savvy]
+fn sk_as_pdf(
+    size: IntegerSexp,
+    pictures: savvy::ListSexp,
+    page_size: NumericSexp,
+    metadata: StringSexp,
+    creation: IntegerSexp,
+) -> savvy::Result<savvy::Sexp> {
+    canvas::validate_size(size.as_slice())?;
+    assert_len("page_size", 2, page_size.len())?;
+    assert_len("metadata", 4, metadata.len())?;
 
-    let mut ret = savvy::OwnedRawSexp::new(data.len())?;
-    for (i, b) in data.as_bytes().iter().enumerate() {
-        ret.set_elt(i, *b)?;
+    let size = size.to_vec();
+    let page_size = page_size.as_slice_f64();
+    if page_size.iter().any(|v| !v.is_finite() || *v <= 0.0) {
+        return Err(savvy_err!(
+            "page_size must be positive, but got {}x{}",
+            page_size[0],
+            page_size[1]
+        ));
+    }
+    let creation = canvas::as_pdf_datetime(&creation)?;
+    let pages = read_pictures(pictures)?;
+
+    let meta = metadata.to_vec();
+    let metadata = skia_safe::pdf::Metadata {
+        title: meta[0].to_string(),
+        author: meta[1].to_string(),
+        subject: meta[2].to_string(),
+        keywords: meta[3].to_string(),
+        creator: "skiagd".to_string(),
+        creation,
+        modified: creation,
+        ..Default::default()
+    };
+    let data = canvas::as_pdf(
+        size,
+        pages,
+        (page_size[0] as f32, page_size[1] as f32),
+        &metadata,
+    );
+
+    let ret = savvy::OwnedRawSexp::try_from_slice(data)?;
+    Ok(ret.into())
+}
+
+/// Reads a list of serialized pictures
+fn read_pictures(pictures: savvy::ListSexp) -> savvy::Result<Vec<skia_safe::Picture>> {
+    let mut ret = Vec::new();
+    for (i, picture) in pictures.values_iter().enumerate() {
+        let bytes = savvy::RawSexp::try_from(picture)
+            .map_err(|e| savvy_err!("Failed to read picture at index {}:\n {}", i + 1, e))?;
+        ret.push(read_picture_bytes(&bytes)?);
+    }
+    if ret.is_empty() {
+        return Err(savvy_err!("At least one picture is required"));
     }
+    Ok(ret)
+}
+
+/// Takes a list of pictures and returns APNG data
+///
+/// @param size Canvas size.
+/// @param pictures A list of serialized pictures. Each picture is a frame.
+/// @param delays Delays of frames in seconds.
+/// @param plays Number of times to play the animation. 0 means infinite.
+/// @returns A raw vector of APNG data.
+/// @noRd
+#[savvy]
+fn sk_as_apng(
+    size: IntegerSexp,
+    pictures: savvy::ListSexp,
+    delays: NumericSexp,
+    plays: NumericScalar,
+) -> savvy::Result<savvy::Sexp> {
+    canvas::validate_size(size.as_slice())?;
+
+    let pictures = read_pictures(pictures)?;
+    assert_len("delays", pictures.len(), delays.len())?;
+
+    let data = animation::as_apng(
+        size.to_vec(),
+        pictures,
+        delays.as_slice_f64(),
+        plays.as_i32()?.max(0) as u32,
+    )?;
+
+    let ret = savvy::OwnedRawSexp::try_from_slice(data)?;
     Ok(ret.into())
 }
 
-/// Draws PNG data as an image on canvas
+/// Takes a list of pictures and returns animated GIF data
+///
+/// @param size Canvas size.
+/// @param pictures A list of serialized pictures. Each picture is a frame.
+/// @param delays Delays of frames in seconds.
+/// @param plays Number of times to play the animation. 0 means infinite.
+/// @param speed Sampling factor for color quantization (1-30).
+/// @param dither Whether to apply Floyd-Steinberg dithering.
+/// @returns A raw vector of GIF data.
+/// @noRd
+#[savvy]
+fn sk_as_gif(
+    size: IntegerSexp,
+    pictures: savvy::ListSexp,
+    delays: NumericSexp,
+    plays: NumericScalar,
+    speed: NumericScalar,
+    dither: LogicalSexp,
+) -> savvy::Result<savvy::Sexp> {
+    canvas::validate_size(size.as_slice())?;
+
+    let pictures = read_pictures(pictures)?;
+    assert_len("delays", pictures.len(), delays.len())?;
+
+    let data = animation::as_gif(
+        size.to_vec(),
+        pictures,
+        delays.as_slice_f64(),
+        plays.as_i32()?.max(0) as u32,
+        speed.as_i32()?,
+        dither.to_vec()[0],
+    )?;
+
+    let ret = savvy::OwnedRawSexp::try_from_slice(data)?;
+    Ok(ret.into())
+}
+
+/// Takes a raw vector of picture and writes it into a PNG file tile by tile
 ///
 /// @param size Canvas size.
 /// @param curr_bytes Current canvas state.
-/// @param props PaintAttrs.
-/// @param png_bytes PNG data to draw.
-/// @param left_top Offset for drawing PNG image.
-/// @returns A raw vector of picture.
+/// @param path Path to the output file.
+/// @param tile_size Width and height of tiles in pixels.
+/// @param compression zlib compression level (0-9).
+/// @param scale Device scale factor.
 /// @noRd
 #[savvy]
-fn sk_draw_png(
+fn sk_as_png_tiled(
     size: IntegerSexp,
     curr_bytes: savvy::RawSexp,
-    props: PaintAttrs,
+    path: StringSexp,
+    tile_size: NumericScalar,
+    compression: NumericScalar,
+    scale: NumericScalar,
+) -> savvy::Result<()> {
+    canvas::validate_size(size.as_slice())?;
+    let picture = read_picture_bytes(&curr_bytes)?;
+    tiled::write_png_tiled(
+        path.to_vec()[0],
+        size.to_vec(),
+        &picture,
+        tile_size.as_i32()?,
+        scale.as_f64(),
+        compression.as_i32()?,
+    )
+}
+
+/// Takes an argument of a drawing operation by position
+fn op_arg<T>(args: &savvy::ListSexp, index: usize) -> anyhow::Result<T, savvy::Error>
+where
+    T: TryFrom<savvy::Sexp, Error = savvy::Error>,
+{
+    let arg = args
+        .get_by_index(index)
+        .ok_or_else(|| return savvy_err!("Missing argument at index {}", index + 1))?;
+    T::try_from(arg)
+}
+
+/// Draws a list of operations onto canvas in order
+///
+/// Each operation is a list of `kind`, `props`, and `args`,
+/// where `args` are passed positionally to the drawing function for `kind`.
+///
+/// @param target SkiaCanvas to draw on.
+/// @param ops A list of drawing operations.
+/// @returns `NULL` invisibly.
+/// @noRd
+#[savvy]
+fn sk_draw_ops(target: &mut SkiaCanvas, ops: savvy::ListSexp) -> savvy::Result<()> {
+    for (i, op) in ops.values_iter().enumerate() {
+        let op = savvy::ListSexp::try_from(op)?;
+        let kind: &str = op_arg(&op, 0)?;
+        let props: &PaintAttrs = op_arg(&op, 1)?;
+        let args: savvy::ListSexp = op_arg(&op, 2)?;
+        let a = &args;
+        match kind {
+            "arc" => sk_draw_arc(
+                target,
+                props,
+                op_arg(a, 0)?,
+                op_arg(a, 1)?,
+                op_arg(a, 2)?,
+                op_arg(a, 3)?,
+                op_arg(a, 4)?,
+                op_arg(a, 5)?,
+                op_arg(a, 6)?,
+                op_arg(a, 7)?,
+            )?,
+            "atlas" => sk_draw_atlas(target, props, op_arg(a, 0)?, op_arg(a, 1)?)?,
+            "circle" => sk_draw_circle(
+                target,
+                props,
+                op_arg(a, 0)?,
+                op_arg(a, 1)?,
+                op_arg(a, 2)?,
+                op_arg(a, 3)?,
+                op_arg(a, 4)?,
+                op_arg(a, 5)?,
+            )?,
+            "clip_path" => sk_clip_path(
+                target,
+                op_arg(a, 0)?,
+                op_arg(a, 1)?,
+                op_arg(a, 2)?,
+                op_arg(a, 3)?,
+            )?,
+            "clip_rect" => sk_clip_rect(
+                target,
+                op_arg(a, 0)?,
+                op_arg(a, 1)?,
+                op_arg(a, 2)?,
+                op_arg(a, 3)?,
+            )?,
+            "diff_rect" => sk_draw_diff_rect(
+                target,
+                props,
+                op_arg(a, 0)?,
+                op_arg(a, 1)?,
+                op_arg(a, 2)?,
+                op_arg(a, 3)?,
+                op_arg(a, 4)?,
+                op_arg(a, 5)?,
+                op_arg(a, 6)?,
+                op_arg(a, 7)?,
+                op_arg(a, 8)?,
+                op_arg(a, 9)?,
+            )?,
+            "line" => sk_draw_line(
+                target,
+                props,
+                op_arg(a, 0)?,
+                op_arg(a, 1)?,
+                op_arg(a, 2)?,
+                op_arg(a, 3)?,
+                op_arg(a, 4)?,
+                op_arg(a, 5)?,
+                op_arg(a, 6)?,
+            )?,
+            "paragraph" => sk_draw_paragraph(
+                target,
+                props,
+                op_arg(a, 0)?,
+                op_arg(a, 1)?,
+                op_arg(a, 2)?,
+                op_arg(a, 3)?,
+                op_arg(a, 4)?,
+                op_arg(a, 5)?,
+                op_arg(a, 6)?,
+                op_arg(a, 7)?,
+                op_arg(a, 8)?,
+                op_arg(a, 9)?,
+            )?,
+            "path" => sk_draw_path(
+                target,
+                props,
+                op_arg(a, 0)?,
+                op_arg(a, 1)?,
+                op_arg(a, 2)?,
+                op_arg(a, 3)?,
+                op_arg(a, 4)?,
+                op_arg(a, 5)?,
+            )?,
+            "picture" => sk_draw_picture(
+                target,
+                props,
+                op_arg(a, 0)?,
+                op_arg(a, 1)?,
+                op_arg(a, 2)?,
+            )?,
+            "png" => sk_draw_png(target, props, op_arg(a, 0)?, op_arg(a, 1)?)?,
+            "raster" => sk_draw_raster(
+                target,
+                props,
+                op_arg(a, 0)?,
+                op_arg(a, 1)?,
+                op_arg(a, 2)?,
+                op_arg(a, 3)?,
+                op_arg(a, 4)?,
+            )?,
+            "points" => sk_draw_points(
+                target,
+                props,
+                op_arg(a, 0)?,
+                op_arg(a, 1)?,
+                op_arg(a, 2)?,
+                op_arg(a, 3)?,
+                op_arg(a, 4)?,
+                op_arg(a, 5)?,
+                op_arg(a, 6)?,
+            )?,
+            "restore" => sk_restore(target, op_arg(a, 0)?)?,
+            "rich_text" => sk_draw_rich_text(
+                target,
+                props,
+                op_arg(a, 0)?,
+                op_arg(a, 1)?,
+                op_arg(a, 2)?,
+                op_arg(a, 3)?,
+                op_arg(a, 4)?,
+                op_arg(a, 5)?,
+                op_arg(a, 6)?,
+            )?,
+            "rounded_rect" => sk_draw_rounded_rect(
+                target,
+                props,
+                op_arg(a, 0)?,
+                op_arg(a, 1)?,
+                op_arg(a, 2)?,
+                op_arg(a, 3)?,
+                op_arg(a, 4)?,
+                op_arg(a, 5)?,
+                op_arg(a, 6)?,
+            )?,
+            "save_layer" => {
+                sk_save_layer(target, props, op_arg(a, 0)?, op_arg(a, 1)?, op_arg(a, 2)?)?
+            }
+            "text" => sk_draw_text(
+                target,
+                props,
+                op_arg(a, 0)?,
+                op_arg(a, 1)?,
+                op_arg(a, 2)?,
+                op_arg(a, 3)?,
+                op_arg(a, 4)?,
+            )?,
+            "text_on_path" => sk_draw_text_on_path(
+                target,
+                props,
+                op_arg(a, 0)?,
+                op_arg(a, 1)?,
+                op_arg(a, 2)?,
+                op_arg(a, 3)?,
+                op_arg(a, 4)?,
+                op_arg(a, 5)?,
+                op_arg(a, 6)?,
+                op_arg(a, 7)?,
+                op_arg(a, 8)?,
+            )?,
+            "transform" => sk_transform(
+                target,
+                op_arg(a, 0)?,
+                op_arg(a, 1)?,
+                op_arg(a, 2)?,
+                op_arg(a, 3)?,
+                op_arg(a, 4)?,
+                op_arg(a, 5)?,
+            )?,
+            "vertices" => sk_draw_vertices(
+                target,
+                props,
+                op_arg(a, 0)?,
+                op_arg(a, 1)?,
+                op_arg(a, 2)?,
+                op_arg(a, 3)?,
+                op_arg(a, 4)?,
+            )?,
+            _ => {
+                return Err(savvy_err!("Unknown operation '{}' at index {}", kind, i + 1));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns a paint for compositing a layer or a picture
+///
+/// Only opacity, blend mode, and image filter are taken into account.
+fn layer_paint(props: &PaintAttrs, opacity: f64) -> skia_safe::Paint {
+    let mut paint = skia_safe::Paint::default();
+    paint.set_alpha_f(opacity.clamp(0.0, 1.0) as f32);
+    paint.set_blend_mode(props.paint.blend_mode_or(skia_safe::BlendMode::SrcOver));
+    if let Some(filter) = props.paint.image_filter() {
+        paint.set_image_filter(filter);
+    }
+    paint
+}
+
+/// Saves the canvas state and begins a new layer
+///
+/// Subsequent drawing goes to the layer until `sk_restore()` is called,
+/// then the layer is composited onto the canvas with the layer paint.
+///
+/// @param target SkiaCanvas to draw on.
+/// @param props PaintAttrs. Only blend mode and image filter are used for the layer.
+/// @param opacity Opacity of the layer (0-1).
+/// @param bounds Bounds of the layer. If empty, the layer is unbounded.
+/// @param backdrop ImageFilter applied to the content beneath the layer.
+fn sk_save_layer(
+    target: &mut SkiaCanvas,
+    props: &PaintAttrs,
+    opacity: NumericScalar,
+    bounds: NumericSexp,
+    backdrop: &paint_attrs::ImageFilter,
+) -> savvy::Result<()> {
+    let bounds = match bounds.len() {
+        0 => None,
+        4 => {
+            let b = bounds.as_slice_f64();
+            Some(skia_safe::Rect::new(b[0] as f32, b[1] as f32, b[2] as f32, b[3] as f32))
+        }
+        _ => return Err(savvy_err!("bounds must have 0 or 4 elements")),
+    };
+
+    let paint = layer_paint(props, opacity.as_f64());
+    let mut rec = skia_safe::canvas::SaveLayerRec::default().paint(&paint);
+    if let Some(bounds) = &bounds {
+        rec = rec.bounds(bounds);
+    }
+    if let Some(filter) = &backdrop.filter {
+        rec = rec.backdrop(filter);
+    }
+    target.recording_canvas()?.save_layer(&rec);
+    target.push_save(canvas::SaveKind::Layer);
+    Ok(())
+}
+
+/// Saves the canvas state and clips the canvas by a rounded rectangle
+///
+/// Subsequent drawing is restricted to the clip until `sk_restore()` is called.
+///
+/// @param target SkiaCanvas to draw on.
+/// @param ltrb Rectangle.
+/// @param radii Axis lengths on X-axis and Y-axis of oval describing rounded corners.
+/// @param op ClipOp. One of "intersect" or "difference".
+/// @param anti_alias Whether to anti-alias the clip edges.
+fn sk_clip_rect(
+    target: &mut SkiaCanvas,
+    ltrb: NumericSexp,
+    radii: NumericSexp,
+    op: StringSexp,
+    anti_alias: LogicalSexp,
+) -> savvy::Result<()> {
+    assert_len("ltrb", 4, ltrb.len())?;
+    assert_len("radii", 2, radii.len())?;
+
+    let ltrb = ltrb.as_slice_f64();
+    let radii = radii.as_slice_f64();
+    let rect = skia_safe::RRect::new_rect_xy(
+        skia_safe::Rect::new(
+            ltrb[0] as f32,
+            ltrb[1] as f32,
+            ltrb[2] as f32,
+            ltrb[3] as f32,
+        ),
+        radii[0] as f32,
+        radii[1] as f32,
+    );
+    let op = canvas::clip_op(op.to_vec()[0])?;
+
+    let canvas = target.recording_canvas()?;
+    canvas.save();
+    canvas.clip_rrect(rect, op, anti_alias.to_vec()[0]);
+    target.push_save(canvas::SaveKind::Clip);
+    Ok(())
+}
+
+/// Saves the canvas state and clips the canvas by an SVG path
+///
+/// Subsequent drawing is restricted to the clip until `sk_restore()` is called.
+///
+/// @param target SkiaCanvas to draw on.
+/// @param svg SVG string of the path.
+/// @param fill_type FillType.
+/// @param op ClipOp. One of "intersect" or "difference".
+/// @param anti_alias Whether to anti-alias the clip edges.
+fn sk_clip_path(
+    target: &mut SkiaCanvas,
+    svg: StringSexp,
+    fill_type: &paint_attrs::FillType,
+    op: StringSexp,
+    anti_alias: LogicalSexp,
+) -> savvy::Result<()> {
+    assert_len("svg", 1, svg.len())?;
+
+    let mut path = skia_safe::utils::parse_path::from_svg(svg.to_vec()[0])
+        .ok_or_else(|| return savvy_err!("Failed to parse svg"))?;
+    path.set_fill_type(paint_attrs::sk_fill_type(&fill_type));
+    let op = canvas::clip_op(op.to_vec()[0])?;
+
+    let canvas = target.recording_canvas()?;
+    canvas.save();
+    canvas.clip_path(&path, op, anti_alias.to_vec()[0]);
+    target.push_save(canvas::SaveKind::Clip);
+    Ok(())
+}
+
+/// Saves the canvas state and transforms the canvas
+///
+/// Subsequent drawing is transformed until `sk_restore()` is called.
+/// Shapes are transformed by `matrix`, `skew`, `scale`, `rotate`, and `translate` in this order.
+///
+/// @param target SkiaCanvas to draw on.
+/// @param translate Translation on X-axis and Y-axis.
+/// @param rotate Rotation in degrees.
+/// @param pivot Pivot point of rotation.
+/// @param scale Scale factors on X-axis and Y-axis.
+/// @param skew Skew factors on X-axis and Y-axis.
+/// @param matrix 3x3 or 4x4 matrix to concatenate. If empty, it is ignored.
+fn sk_transform(
+    target: &mut SkiaCanvas,
+    translate: NumericSexp,
+    rotate: NumericScalar,
+    pivot: NumericSexp,
+    scale: NumericSexp,
+    skew: NumericSexp,
+    matrix: NumericSexp,
+) -> savvy::Result<()> {
+    assert_len("translate", 2, translate.len())?;
+    assert_len("pivot", 2, pivot.len())?;
+    assert_len("scale", 2, scale.len())?;
+    assert_len("skew", 2, skew.len())?;
+
+    let translate = translate.as_slice_f64();
+    let pivot = pivot.as_slice_f64();
+    let scale = scale.as_slice_f64();
+    let skew = skew.as_slice_f64();
+    let matrix = match matrix.len() {
+        0 => None,
+        9 => {
+            let matrix = path_transform::as_matrix(&matrix)
+                .ok_or_else(|| return savvy_err!("Failed to parse matrix"))?;
+            Some(skia_safe::M44::from(&matrix[0]))
+        }
+        16 => {
+            let mut m = [0.0f32; 16];
+            for (i, v) in matrix.iter_f64().enumerate() {
+                m[i] = v as f32;
+            }
+            Some(skia_safe::M44::row_major(&m))
+        }
+        _ => return Err(savvy_err!("matrix must have 9 or 16 elements")),
+    };
+
+    let canvas = target.recording_canvas()?;
+    canvas.save();
+    canvas.translate((translate[0] as f32, translate[1] as f32));
+    canvas.rotate(
+        rotate.as_f64() as f32,
+        Some(skia_safe::Point::new(pivot[0] as f32, pivot[1] as f32)),
+    );
+    canvas.scale((scale[0] as f32, scale[1] as f32));
+    canvas.skew((skew[0] as f32, skew[1] as f32));
+    if let Some(matrix) = &matrix {
+        canvas.concat_44(matrix);
+    }
+    target.push_save(canvas::SaveKind::Transform);
+    Ok(())
+}
+
+/// Restores the canvas state saved by the last `sk_save_layer()` or the like
+///
+/// The last saved state must be of `kind`,
+/// so that e.g. `end_clip()` does not close a layer.
+///
+/// @param target SkiaCanvas to draw on.
+/// @param kind Kind of the saved state. One of "layer", "clip", or "transform".
+fn sk_restore(target: &mut SkiaCanvas, kind: StringSexp) -> savvy::Result<()> {
+    assert_len("kind", 1, kind.len())?;
+    let kind = canvas::SaveKind::from_name(kind.to_vec()[0])?;
+    target.restore(kind)?;
+    Ok(())
+}
+
+/// Draws encoded image data as an image on canvas
+///
+/// @param target SkiaCanvas to draw on.
+/// @param props PaintAttrs.
+/// @param png_bytes Encoded image data to draw.
+/// @param left_top Offset for drawing image.
+fn sk_draw_png(
+    target: &mut SkiaCanvas,
+    props: &PaintAttrs,
     png_bytes: savvy::RawSexp,
     left_top: NumericSexp,
-) -> savvy::Result<savvy::Sexp> {
+) -> savvy::Result<()> {
     assert_len("left_top", 2, left_top.len())?;
 
-    let picture = read_picture_bytes(&curr_bytes)?;
     let left_top = left_top.as_slice_f64();
 
-    let input = Data::new_copy(png_bytes.as_slice());
-    let picture = canvas::put_png(input, size, picture, left_top.to_vec(), props)?;
+    let image = canvas::read_image_bytes(png_bytes.as_slice())?;
+    canvas::put_image(&image, target.recording_canvas()?, left_top.to_vec(), props);
 
-    Ok(picture.into())
+    Ok(())
+}
+
+/// Draws a picture on canvas
+///
+/// The picture is drawn as if it were drawn into a layer,
+/// which is then composited onto the canvas with the layer paint.
+///
+/// @param target SkiaCanvas to draw on.
+/// @param props PaintAttrs. Only blend mode and image filter are used.
+/// @param picture Serialized picture to draw.
+/// @param matrix Matrix to transform the picture with. If empty, no transform is applied.
+/// @param opacity Opacity of the picture (0-1).
+fn sk_draw_picture(
+    target: &mut SkiaCanvas,
+    props: &PaintAttrs,
+    picture: savvy::RawSexp,
+    matrix: NumericSexp,
+    opacity: NumericScalar,
+) -> savvy::Result<()> {
+    let matrix = match matrix.len() {
+        0 => None,
+        9 => {
+            let matrix = path_transform::as_matrix(&matrix)
+                .ok_or_else(|| return savvy_err!("Failed to parse matrix"))?;
+            Some(matrix[0])
+        }
+        _ => return Err(savvy_err!("matrix must have 0 or 9 elements")),
+    };
+    let picture = read_picture_bytes(&picture)?;
+    let paint = layer_paint(props, opacity.as_f64());
+
+    target
+        .recording_canvas()?
+        .draw_picture(&picture, matrix.as_ref(), Some(&paint));
+    Ok(())
+}
+
+/// Draws native raster as an image on canvas
+///
+/// @param target SkiaCanvas to draw on.
+/// @param props PaintAttrs.
+/// @param raster Integers of native raster.
+/// @param size Width and height of native raster.
+/// @param premultiplied Whether colors in native raster are premultiplied by alpha.
+/// @param ltrb Destination rectangle.
+/// @param sampling Sampling method.
+fn sk_draw_raster(
+    target: &mut SkiaCanvas,
+    props: &PaintAttrs,
+    raster: IntegerSexp,
+    size: IntegerSexp,
+    premultiplied: LogicalSexp,
+    ltrb: NumericSexp,
+    sampling: StringSexp,
+) -> savvy::Result<()> {
+    assert_len("size", 2, size.len())?;
+    assert_len("ltrb", 4, ltrb.len())?;
+
+    let size = size.as_slice();
+    let ltrb = ltrb.as_slice_f64();
+    let image = canvas::read_native_raster(&raster, size[0], size[1], premultiplied.to_vec()[0])?;
+    let sampling = canvas::sampling_options(sampling.to_vec()[0])?;
+
+    let canvas = target.recording_canvas()?;
+    canvas.draw_image_rect_with_sampling_options(
+        &image,
+        None,
+        skia_safe::Rect::new(
+            ltrb[0] as f32,
+            ltrb[1] as f32,
+            ltrb[2] as f32,
+            ltrb[3] as f32,
+        ),
+        sampling,
+        &props.paint,
+    );
+    Ok(())
+}
+
+/// Draws textblobs with their own sigma and color
+///
+/// If `freeze` is `true`, textblobs are rasterized before being added to the target.
+fn draw_text_blobs(
+    target: &mut SkiaCanvas,
+    props: &PaintAttrs,
+    blobs: &[Option<skia_safe::TextBlob>],
+    freeze: bool,
+    sigma: &[f64],
+    color: &[skia_safe::Color],
+) -> savvy::Result<()> {
+    let mut props = props.clone();
+    let blend_mode = props.paint.blend_mode_or(skia_safe::BlendMode::SrcOver);
+    let (width, height) = target.size();
+    // Textblobs to be frozen are recorded separately, so that the recording of the target
+    // is not finished and layers, clips, and transforms opened on it are kept
+    let mut layer = skia_safe::PictureRecorder::new();
+    let canvas = if freeze {
+        props.paint.set_blend_mode(skia_safe::BlendMode::SrcOver);
+        layer.begin_recording(skia_safe::Rect::from_isize((width, height)), false)
+    } else {
+        target.recording_canvas()?
+    };
+
+    for (i, blob) in blobs.iter().enumerate() {
+        let Some(blob) = blob else {
+            continue;
+        };
+        props.reset_blur(sigma[i]);
+        props.reset_color(color[i]);
+        canvas.draw_text_blob(blob, (0.0, 0.0), &props.paint);
+    }
+
+    if freeze {
+        let result = layer
+            .finish_recording_as_picture(None)
+            .ok_or_else(|| return savvy_err!("Failed to finish recording"))?;
+        // The rasterized textblobs are encoded as PNG once, then drawn onto the target
+        // with the blend mode of `props`
+        let data = canvas::as_png(vec![width, height], result)?;
+        let image = canvas::read_image_bytes(data.as_bytes())?;
+        let mut paint = skia_safe::Paint::default();
+        paint.set_blend_mode(blend_mode);
+        target
+            .recording_canvas()?
+            .draw_image(&image, (0.0, 0.0), Some(&paint));
+    }
+    Ok(())
 }
 
 /// Draws text as textblobs
 ///
-/// @param size Canvas size.
-/// @param curr_bytes Current canvas state.
+/// @param target SkiaCanvas to draw on.
 /// @param props PaintAttrs.
 /// @param text Text strings.
 /// @param freeze Whether to freeze textblobs.
 /// @param rsx_trans RSX transform for each character.
 /// @param sigma Blur sigma.
 /// @param color Colors.
-/// @returns A raw vector of picture.
-/// @noRd
-#[savvy]
 fn sk_draw_text(
-    size: IntegerSexp,
-    curr_bytes: savvy::RawSexp,
-    props: PaintAttrs,
+    target: &mut SkiaCanvas,
+    props: &PaintAttrs,
     text: StringSexp,
     freeze: LogicalSexp,
     rsx_trans: NumericSexp,
     sigma: NumericSexp,
     color: NumericSexp,
-) -> savvy::Result<savvy::Sexp> {
+) -> savvy::Result<()> {
     let typeface =
         paint_attrs::font::match_family_style(props.font_family.as_str(), props.font_face)?;
     let font = skia_safe::Font::from_typeface(&typeface, props.font_size);
-    let sigma = sigma.as_slice_f64();
-    let color = paint_attrs::num2colors(&color).unwrap_or_else(|| {
-        // if matrix is too small to take color, implicitly use paint color
-        let mut ret: Vec<skia_safe::Color> = Vec::new();
-        ret.resize(text.len(), props.paint.color());
-        ret
-    });
+    let n = recycled_len(&[
+        ("text", text.len()),
+        ("sigma", sigma.len()),
+        ("color", paint_attrs::colors_len(&color)),
+    ])?;
+    let text = recycle("text", text.to_vec(), n)?;
+    let sigma = recycle("sigma", sigma.as_slice_f64().to_vec(), n)?;
+    let color = paint_attrs::recycle_colors(&color, props.paint.color(), n)?;
     let transforms = path_transform::as_rsx_trans(&rsx_trans)
         .ok_or_else(|| return savvy_err!("Failed to parse rsx_trans"))?;
 
-    let picture = read_picture_bytes(&curr_bytes)?;
-    let mut props = props.clone();
-
-    let mut recorder = SkiaCanvas::setup(&size)?;
-    let canvas = recorder.start_recording();
-    picture.playback(canvas);
-
+    let mut blobs = Vec::with_capacity(n);
     let mut trans_offset = 0;
     for (i, t) in text.iter().enumerate() {
         let chars = t.to_string();
@@ -161,41 +1067,172 @@ fn sk_draw_text(
 
         let blob = skia_safe::TextBlob::from_rsxform(&chars, trans, &font)
             .ok_or_else(|| return savvy_err!("Failed to create text blob at index {}", i + 1))?;
+        blobs.push(Some(blob));
+    }
+    draw_text_blobs(target, props, &blobs, freeze.to_vec()[0], &sigma, &color)
+}
+
+/// Draws text along SVG paths as textblobs
+///
+/// @param target SkiaCanvas to draw on.
+/// @param props PaintAttrs.
+/// @param text Text strings.
+/// @param svg SVG notations of paths for each text.
+/// @param freeze Whether to freeze textblobs.
+/// @param start Distance along the path added to the aligned start position.
+/// @param align Text alignment along the path.
+/// @param baseline_shift Distance to shift glyphs perpendicular to the path.
+/// @param overflow What to do with glyphs that do not fit within the path.
+/// @param sigma Blur sigma.
+/// @param color Colors.
+fn sk_draw_text_on_path(
+    target: &mut SkiaCanvas,
+    props: &PaintAttrs,
+    text: StringSexp,
+    svg: StringSexp,
+    freeze: LogicalSexp,
+    start: NumericScalar,
+    align: StringSexp,
+    baseline_shift: NumericScalar,
+    overflow: StringSexp,
+    sigma: NumericSexp,
+    color: NumericSexp,
+) -> savvy::Result<()> {
+    let typeface =
+        paint_attrs::font::match_family_style(props.font_family.as_str(), props.font_face)?;
+    let font = skia_safe::Font::from_typeface(&typeface, props.font_size);
+    let n = recycled_len(&[
+        ("text", text.len()),
+        ("svg", svg.len()),
+        ("sigma", sigma.len()),
+        ("color", paint_attrs::colors_len(&color)),
+    ])?;
+    let text = recycle("text", text.to_vec(), n)?;
+    let svg = recycle("svg", svg.to_vec(), n)?;
+    let sigma = recycle("sigma", sigma.as_slice_f64().to_vec(), n)?;
+    let color = paint_attrs::recycle_colors(&color, props.paint.color(), n)?;
+    let options = text_path::TextPathOptions::new(
+        start.as_f64(),
+        align.to_vec()[0],
+        baseline_shift.as_f64(),
+        overflow.to_vec()[0],
+    )?;
+
+    let mut blobs = Vec::with_capacity(n);
+    for (i, (t, s)) in text.iter().zip(svg.iter()).enumerate() {
+        let path = skia_safe::utils::parse_path::from_svg(s)
+            .ok_or_else(|| return savvy_err!("Failed to parse svg at index {}", i + 1))?;
+        let (font, glyphs, trans) = text_path::place_glyphs(&font, t, &path, &options)?;
+        // Every glyph can be clipped out of the path
+        if glyphs.is_empty() {
+            blobs.push(None);
+            continue;
+        }
+        let blob = skia_safe::TextBlob::from_rsxform(glyphs.as_slice(), &trans, &font)
+            .ok_or_else(|| return savvy_err!("Failed to create text blob at index {}", i + 1))?;
+        blobs.push(Some(blob));
+    }
+    draw_text_blobs(target, props, &blobs, freeze.to_vec()[0], &sigma, &color)
+}
+
+/// Draws text strings as paragraphs
+///
+/// Each element of `text` is laid out as a paragraph wrapped at `max_width`,
+/// and then drawn with its top-left corner at `(x, y)`.
+///
+/// @param target SkiaCanvas to draw on.
+/// @param props PaintAttrs.
+/// @param text Text strings.
+/// @param x X-coordinates of the top-left corners.
+/// @param y Y-coordinates of the top-left corners.
+/// @param max_width Width at which lines are wrapped.
+/// @param align Text alignment.
+/// @param line_height Line height as a multiple of the font size. If empty, the font's own height is used.
+/// @param max_lines Maximum number of lines. If empty, the number of lines is unlimited.
+/// @param ellipsis Text appended to the last line when the paragraph exceeds `max_lines`.
+/// @param sigma Blur sigmas.
+/// @param color Colors.
+fn sk_draw_paragraph(
+    target: &mut SkiaCanvas,
+    props: &PaintAttrs,
+    text: StringSexp,
+    x: NumericSexp,
+    y: NumericSexp,
+    max_width: NumericScalar,
+    align: StringSexp,
+    line_height: NumericSexp,
+    max_lines: NumericSexp,
+    ellipsis: StringSexp,
+    sigma: NumericSexp,
+    color: NumericSexp,
+) -> savvy::Result<()> {
+    let n = recycled_len(&[
+        ("text", text.len()),
+        ("x", x.len()),
+        ("y", y.len()),
+        ("sigma", sigma.len()),
+        ("color", paint_attrs::colors_len(&color)),
+    ])?;
+    let text = recycle("text", text.to_vec(), n)?;
+    let x = recycle("x", x.as_slice_f64().to_vec(), n)?;
+    let y = recycle("y", y.as_slice_f64().to_vec(), n)?;
+    let sigma = recycle("sigma", sigma.as_slice_f64().to_vec(), n)?;
+    let color = paint_attrs::recycle_colors(&color, props.paint.color(), n)?;
+
+    let options =
+        paragraph::ParagraphOptions::new(max_width, align, line_height, max_lines, ellipsis)?;
 
+    let mut props = props.clone();
+    let canvas = target.recording_canvas()?;
+    for (i, t) in text.iter().enumerate() {
         props.reset_blur(sigma[i]);
         props.reset_color(color[i]);
-        canvas.draw_text_blob(&blob, (0.0, 0.0), &props.paint);
+        let spans = [(t.to_string(), paragraph::text_style(&props))];
+        let paragraph = paragraph::layout(&spans, &options);
+        paragraph.paint(canvas, (x[i] as f32, y[i] as f32));
     }
+    Ok(())
+}
 
-    // manually finish recording
-    let result = recorder
-        .recorder
-        .finish_recording_as_picture(None)
-        .ok_or_else(|| return savvy_err!("Failed to finish recording"))?;
-
-    let freeze = freeze.to_vec();
-
-    if !freeze[0] {
-        // if `freeze` is false, returns the picture
-        let d = result.serialize();
-        let bytes = d.as_bytes();
-        let mut ret = savvy::OwnedRawSexp::new(bytes.len())?;
-        for (i, b) in bytes.iter().enumerate() {
-            ret.set_elt(i, *b)?;
-        }
-        Ok(ret.into())
-    } else {
-        // otherwise, once encodes the result as PNG and redraws it
-        let data = canvas::as_png(size.to_vec(), result).ok_or_else(|| return savvy_err!("Failed to encode PNG"))?;
-        let ret = canvas::put_png(data, size, picture, vec![0.0, 0.0], props)?;
-        Ok(ret.into())
-    }
+/// Draws spans of text with their own styles as a paragraph
+///
+/// @param target SkiaCanvas to draw on.
+/// @param props PaintAttrs.
+/// @param spans A list of columns that describe text and styles of spans.
+/// @param left_top Top-left corner of the paragraph.
+/// @param max_width Width at which lines are wrapped.
+/// @param align Text alignment.
+/// @param line_height Line height as a multiple of the font size. If empty, the font's own height is used.
+/// @param max_lines Maximum number of lines. If empty, the number of lines is unlimited.
+/// @param ellipsis Text appended to the last line when the paragraph exceeds `max_lines`.
+fn sk_draw_rich_text(
+    target: &mut SkiaCanvas,
+    props: &PaintAttrs,
+    spans: savvy::ListSexp,
+    left_top: NumericSexp,
+    max_width: NumericScalar,
+    align: StringSexp,
+    line_height: NumericSexp,
+    max_lines: NumericSexp,
+    ellipsis: StringSexp,
+) -> savvy::Result<()> {
+    assert_len("left_top", 2, left_top.len())?;
+    let left_top = left_top.as_slice_f64();
+
+    let options =
+        paragraph::ParagraphOptions::new(max_width, align, line_height, max_lines, ellipsis)?;
+    let spans = paragraph::read_spans(&spans, props)?;
+    let paragraph = paragraph::layout(&spans, &options);
+    paragraph.paint(
+        target.recording_canvas()?,
+        (left_top[0] as f32, left_top[1] as f32),
+    );
+    Ok(())
 }
 
 /// Draws SVG paths
 ///
-/// @param size Canvas size.
-/// @param curr_bytes Current canvas state.
+/// @param target SkiaCanvas to draw on.
 /// @param props PaintAttrs.
 /// @param svg SVG strings to draw.
 /// @param rsx_trans RSX transform for each path.
@@ -203,37 +1240,33 @@ fn sk_draw_text(
 /// @param width Stroke width.
 /// @param color Colors.
 /// @param fill_type FillType.
-/// @returns A raw vector of picture.
-/// @noRd
-#[savvy]
 fn sk_draw_path(
-    size: IntegerSexp,
-    curr_bytes: savvy::RawSexp,
-    props: PaintAttrs,
+    target: &mut SkiaCanvas,
+    props: &PaintAttrs,
     svg: StringSexp,
     rsx_trans: NumericSexp,
     sigma: NumericSexp,
     width: NumericSexp,
     color: NumericSexp,
     fill_type: &paint_attrs::FillType,
-) -> savvy::Result<savvy::Sexp> {
-    let sigma = sigma.as_slice_f64();
-    let width = width.as_slice_f64();
-    let color = paint_attrs::num2colors(&color).unwrap_or_else(|| {
-        // if matrix is too small to take color, implicitly use paint color
-        let mut ret: Vec<skia_safe::Color> = Vec::new();
-        ret.resize(width.len(), props.paint.color());
-        ret
-    });
+) -> savvy::Result<()> {
     let transforms = path_transform::as_rsx_trans(&rsx_trans)
         .ok_or_else(|| return savvy_err!("Failed to parse rsx_trans"))?;
+    let n = recycled_len(&[
+        ("svg", svg.len()),
+        ("rsx_trans", transforms.len()),
+        ("sigma", sigma.len()),
+        ("width", width.len()),
+        ("color", paint_attrs::colors_len(&color)),
+    ])?;
+    let svg = recycle("svg", svg.to_vec(), n)?;
+    let transforms = recycle("rsx_trans", transforms, n)?;
+    let sigma = recycle("sigma", sigma.as_slice_f64().to_vec(), n)?;
+    let width = recycle("width", width.as_slice_f64().to_vec(), n)?;
+    let color = paint_attrs::recycle_colors(&color, props.paint.color(), n)?;
 
-    let picture = read_picture_bytes(&curr_bytes)?;
     let mut props = props.clone();
-
-    let mut recorder = SkiaCanvas::setup(&size)?;
-    let canvas = recorder.start_recording();
-    picture.playback(canvas);
+    let canvas = target.recording_canvas()?;
 
     for (i, s) in svg.iter().enumerate() {
         props.reset_blur(sigma[i]);
@@ -245,14 +1278,12 @@ fn sk_draw_path(
             .with_transform(&skia_safe::Matrix::default().set_rsxform(&transforms[i]));
         canvas.draw_path(&path, &props.paint);
     }
-    let picture = recorder.finish_recording()?;
-    Ok(picture.into())
+    Ok(())
 }
 
 /// Draws points with specified mode
 ///
-/// @param size Canvas size.
-/// @param curr_bytes Current canvas state.
+/// @param target SkiaCanvas to draw on.
 /// @param props PaintAttrs.
 /// @param x X coordinates of points.
 /// @param y Y coordinates of points.
@@ -261,13 +1292,9 @@ fn sk_draw_path(
 /// @param width Stroke width.
 /// @param color Colors.
 /// @param mode PointMode.
-/// @returns A raw vector of picture.
-/// @noRd
-#[savvy]
 fn sk_draw_points(
-    size: IntegerSexp,
-    curr_bytes: savvy::RawSexp,
-    props: PaintAttrs,
+    target: &mut SkiaCanvas,
+    props: &PaintAttrs,
     x: NumericSexp,
     y: NumericSexp,
     group: NumericSexp,
@@ -275,50 +1302,37 @@ fn sk_draw_points(
     width: NumericSexp,
     color: NumericSexp,
     mode: &paint_attrs::PointMode,
-) -> savvy::Result<savvy::Sexp> {
+) -> savvy::Result<()> {
     let mode = paint_attrs::sk_point_mode(&mode);
-    let x = x.as_slice_f64();
-    let y = y.as_slice_f64();
-    let sigma = sigma.as_slice_f64();
-    let width = width.as_slice_f64();
-    let color = paint_attrs::num2colors(&color).unwrap_or_else(|| {
-        // if matrix is too small to take color, implicitly use paint color
-        let mut ret: Vec<skia_safe::Color> = Vec::new();
-        ret.resize(width.len(), props.paint.color());
-        ret
-    });
+    let points = path_transform::as_points(&x, &y)?;
+    // Groups are run lengths of points, so the number of groups is not recycled
+    let n = group.len();
+    let sigma = recycle("sigma", sigma.as_slice_f64().to_vec(), n)?;
+    let width = recycle("width", width.as_slice_f64().to_vec(), n)?;
+    let color = paint_attrs::recycle_colors(&color, props.paint.color(), n)?;
 
-    let picture = read_picture_bytes(&curr_bytes)?;
     let mut props = props.clone();
+    let canvas = target.recording_canvas()?;
 
-    let mut recorder = SkiaCanvas::setup(&size)?;
-    let canvas = recorder.start_recording();
-    picture.playback(canvas);
-
-    let points = x.iter().zip(y.iter());
     let mut offset = 0;
     for (i, grp) in group.iter_usize().enumerate() {
         let grp = grp?;
-        let p = points
-            .clone()
-            .skip(offset)
-            .take(grp)
-            .map(|(x, y)| skia_safe::Point::new(*x as f32, *y as f32))
-            .collect::<Vec<_>>();
+        if offset + grp > points.len() {
+            return Err(savvy_err!("group offset out of bounds at index {}", i + 1));
+        }
+        let p = &points[offset..offset + grp];
         props.reset_blur(sigma[i]);
         props.reset_width(width[i]);
         props.reset_color(color[i]);
-        canvas.draw_points(mode, &p, &props.paint);
+        canvas.draw_points(mode, p, &props.paint);
         offset += grp;
     }
-    let picture = recorder.finish_recording()?;
-    Ok(picture.into())
+    Ok(())
 }
 
 /// Draws lines
 ///
-/// @param size Canvas size.
-/// @param curr_bytes Current canvas state.
+/// @param target SkiaCanvas to draw on.
 /// @param props PaintAttrs.
 /// @param from_x X coordinates of start points.
 /// @param from_y Y coordinates of start points.
@@ -327,14 +1341,9 @@ fn sk_draw_points(
 /// @param sigma Blur sigma.
 /// @param width Stroke width.
 /// @param color Colors.
-/// @returns A raw vector of picture.
-/// @noRd
-#[allow(unused_mut)]
-#[savvy]
 fn sk_draw_line(
-    size: IntegerSexp,
-    curr_bytes: savvy::RawSexp,
-    props: PaintAttrs,
+    target: &mut SkiaCanvas,
+    props: &PaintAttrs,
     from_x: NumericSexp,
     from_y: NumericSexp,
     to_x: NumericSexp,
@@ -342,24 +1351,24 @@ fn sk_draw_line(
     sigma: NumericSexp,
     width: NumericSexp,
     color: NumericSexp,
-) -> savvy::Result<savvy::Sexp> {
-    let from = path_transform::as_points(&from_x, &from_y);
-    let to = path_transform::as_points(&to_x, &to_y);
-    let sigma = sigma.as_slice_f64();
-    let width = width.as_slice_f64();
-    let color = paint_attrs::num2colors(&color).unwrap_or_else(|| {
-        // if matrix is too small to take color, implicitly use paint color
-        let mut ret: Vec<skia_safe::Color> = Vec::new();
-        ret.resize(width.len(), props.paint.color());
-        ret
-    });
+) -> savvy::Result<()> {
+    let from = path_transform::as_points(&from_x, &from_y)?;
+    let to = path_transform::as_points(&to_x, &to_y)?;
+    let n = recycled_len(&[
+        ("from", from.len()),
+        ("to", to.len()),
+        ("sigma", sigma.len()),
+        ("width", width.len()),
+        ("color", paint_attrs::colors_len(&color)),
+    ])?;
+    let from = recycle("from", from, n)?;
+    let to = recycle("to", to, n)?;
+    let sigma = recycle("sigma", sigma.as_slice_f64().to_vec(), n)?;
+    let width = recycle("width", width.as_slice_f64().to_vec(), n)?;
+    let color = paint_attrs::recycle_colors(&color, props.paint.color(), n)?;
 
-    let picture = read_picture_bytes(&curr_bytes)?;
     let mut props = props.clone();
-
-    let mut recorder = SkiaCanvas::setup(&size)?;
-    let canvas = recorder.start_recording();
-    picture.playback(canvas);
+    let canvas = target.recording_canvas()?;
 
     for (i, (from, to)) in from.iter().zip(to.iter()).enumerate() {
         props.reset_blur(sigma[i]);
@@ -367,14 +1376,12 @@ fn sk_draw_line(
         props.reset_color(color[i]);
         canvas.draw_line(*from, *to, &props.paint);
     }
-    let picture = recorder.finish_recording()?;
-    Ok(picture.into())
+    Ok(())
 }
 
 /// Draws circles
 ///
-/// @param size Canvas size.
-/// @param curr_bytes Current canvas state.
+/// @param target SkiaCanvas to draw on.
 /// @param props PaintAttrs.
 /// @param x X coordinates of center.
 /// @param y Y coordinates of center.
@@ -382,51 +1389,45 @@ fn sk_draw_line(
 /// @param sigma Blur sigma.
 /// @param width Stroke width.
 /// @param color Colors.
-/// @returns A raw vector of picture.
-/// @noRd
-#[savvy]
 fn sk_draw_circle(
-    size: IntegerSexp,
-    curr_bytes: savvy::RawSexp,
-    props: PaintAttrs,
+    target: &mut SkiaCanvas,
+    props: &PaintAttrs,
     x: NumericSexp,
     y: NumericSexp,
     radius: NumericSexp,
     sigma: NumericSexp,
     width: NumericSexp,
     color: NumericSexp,
-) -> savvy::Result<savvy::Sexp> {
-    let center = path_transform::as_points(&x, &y);
-    let sigma = sigma.as_slice_f64();
-    let width = width.as_slice_f64();
-    let color = paint_attrs::num2colors(&color).unwrap_or_else(|| {
-        // if matrix is too small to take color, implicitly use paint color
-        let mut ret: Vec<skia_safe::Color> = Vec::new();
-        ret.resize(width.len(), props.paint.color());
-        ret
-    });
+) -> savvy::Result<()> {
+    let center = path_transform::as_points(&x, &y)?;
+    let n = recycled_len(&[
+        ("center", center.len()),
+        ("radius", radius.len()),
+        ("sigma", sigma.len()),
+        ("width", width.len()),
+        ("color", paint_attrs::colors_len(&color)),
+    ])?;
+    let center = recycle("center", center, n)?;
+    let radius = recycle("radius", radius.as_slice_f64().to_vec(), n)?;
+    let sigma = recycle("sigma", sigma.as_slice_f64().to_vec(), n)?;
+    let width = recycle("width", width.as_slice_f64().to_vec(), n)?;
+    let color = paint_attrs::recycle_colors(&color, props.paint.color(), n)?;
 
-    let picture = read_picture_bytes(&curr_bytes)?;
     let mut props = props.clone();
+    let canvas = target.recording_canvas()?;
 
-    let mut recorder = SkiaCanvas::setup(&size)?;
-    let canvas = recorder.start_recording();
-    picture.playback(canvas);
-
-    for (i, (center, radius)) in center.iter().zip(radius.iter_f64()).enumerate() {
+    for (i, (center, radius)) in center.iter().zip(radius.iter()).enumerate() {
         props.reset_blur(sigma[i]);
         props.reset_width(width[i]);
         props.reset_color(color[i]);
-        canvas.draw_circle(*center, radius as f32, &props.paint);
+        canvas.draw_circle(*center, *radius as f32, &props.paint);
     }
-    let picture = recorder.finish_recording()?;
-    Ok(picture.into())
+    Ok(())
 }
 
 /// Draws arcs
 ///
-/// @param size Canvas size.
-/// @param curr_bytes Current canvas state.
+/// @param target SkiaCanvas to draw on.
 /// @param props PaintAttrs.
 /// @param ltrb Rectangles.
 /// @param r Corners radius. This actually doesn't affect the result.
@@ -436,13 +1437,9 @@ fn sk_draw_circle(
 /// @param sigma Blur sigma.
 /// @param width Stroke width.
 /// @param color Colors.
-/// @returns A raw vector of picture.
-/// @noRd
-#[savvy]
 fn sk_draw_arc(
-    size: IntegerSexp,
-    curr_bytes: savvy::RawSexp,
-    props: PaintAttrs,
+    target: &mut SkiaCanvas,
+    props: &PaintAttrs,
     ltrb: NumericSexp,
     r: NumericSexp,
     use_center: LogicalSexp,
@@ -451,40 +1448,41 @@ fn sk_draw_arc(
     sigma: NumericSexp,
     width: NumericSexp,
     color: NumericSexp,
-) -> savvy::Result<savvy::Sexp> {
-    let rects = path_transform::as_rrects(&ltrb, &r, &r)
-        .ok_or_else(|| return savvy_err!("Failed to parse ltrb"))?;
+) -> savvy::Result<()> {
+    let rects = path_transform::as_rrects(&ltrb, &r, &r)?;
     let angle = angle.as_slice_f64();
-    let sigma = sigma.as_slice_f64();
-    let width = width.as_slice_f64();
-    let color = paint_attrs::num2colors(&color).unwrap_or_else(|| {
-        // if matrix is too small to take color, implicitly use paint color
-        let mut ret: Vec<skia_safe::Color> = Vec::new();
-        ret.resize(width.len(), props.paint.color());
-        ret
-    });
+    if angle.len() % 2 != 0 {
+        return Err(savvy_err!("angle must have an even number of elements"));
+    }
     let transforms = path_transform::as_rsx_trans(&rsx_trans)
         .ok_or_else(|| return savvy_err!("Failed to parse rsx_trans"))?;
+    let n = recycled_len(&[
+        ("ltrb", rects.len()),
+        ("angle", angle.len() / 2),
+        ("rsx_trans", transforms.len()),
+        ("sigma", sigma.len()),
+        ("width", width.len()),
+        ("color", paint_attrs::colors_len(&color)),
+    ])?;
+    let rects = recycle("ltrb", rects, n)?;
+    let angle = recycle("angle", angle.chunks(2).collect(), n)?;
+    let transforms = recycle("rsx_trans", transforms, n)?;
+    let sigma = recycle("sigma", sigma.as_slice_f64().to_vec(), n)?;
+    let width = recycle("width", width.as_slice_f64().to_vec(), n)?;
+    let color = paint_attrs::recycle_colors(&color, props.paint.color(), n)?;
     let use_center = use_center.to_vec()[0];
 
-    let picture = read_picture_bytes(&curr_bytes)?;
     let mut props = props.clone();
+    let canvas = target.recording_canvas()?;
 
-    let mut recorder = SkiaCanvas::setup(&size)?;
-    let canvas = recorder.start_recording();
-    picture.playback(canvas);
-
-    for (i, (rect, angle)) in rects.iter().zip(angle.chunks(2)).enumerate() {
-        if angle.len() != 2 {
-            break;
-        }
+    for (i, (rect, angle)) in rects.iter().zip(angle.iter()).enumerate() {
         props.reset_blur(sigma[i]);
         props.reset_width(width[i]);
         props.reset_color(color[i]);
         let rect = rect
             .clone()
             .transform(&skia_safe::Matrix::default().set_rsxform(&transforms[i]))
-            .ok_or_else(|| return savvy_err!("Failed to transform rrect at index {}", i))?;
+            .ok_or_else(|| return savvy_err!("Failed to transform rrect at index {}", i + 1))?;
         canvas.draw_arc(
             rect.bounds(),
             angle[0] as f32,
@@ -493,14 +1491,12 @@ fn sk_draw_arc(
             &props.paint,
         );
     }
-    let picture = recorder.finish_recording()?;
-    Ok(picture.into())
+    Ok(())
 }
 
 /// Draws rounded rectangles
 ///
-/// @param size Canvas size.
-/// @param curr_bytes Current canvas state.
+/// @param target SkiaCanvas to draw on.
 /// @param props PaintAttrs.
 /// @param ltrb Rectangles.
 /// @param rx Axis lengths on X-axis of oval describing rounded corners.
@@ -509,13 +1505,9 @@ fn sk_draw_arc(
 /// @param sigma Blur sigma.
 /// @param width Stroke width.
 /// @param color Colors.
-/// @returns A raw vector of picture.
-/// @noRd
-#[savvy]
 fn sk_draw_rounded_rect(
-    size: IntegerSexp,
-    curr_bytes: savvy::RawSexp,
-    props: PaintAttrs,
+    target: &mut SkiaCanvas,
+    props: &PaintAttrs,
     ltrb: NumericSexp,
     rx: NumericSexp,
     ry: NumericSexp,
@@ -523,26 +1515,25 @@ fn sk_draw_rounded_rect(
     sigma: NumericSexp,
     width: NumericSexp,
     color: NumericSexp,
-) -> savvy::Result<savvy::Sexp> {
-    let rects = path_transform::as_rrects(&ltrb, &rx, &ry)
-        .ok_or_else(|| return savvy_err!("Failed to parse ltrb"))?;
-    let sigma = sigma.as_slice_f64();
-    let width = width.as_slice_f64();
-    let color = paint_attrs::num2colors(&color).unwrap_or_else(|| {
-        // if matrix is too small to take color, implicitly use paint color
-        let mut ret: Vec<skia_safe::Color> = Vec::new();
-        ret.resize(width.len(), props.paint.color());
-        ret
-    });
+) -> savvy::Result<()> {
+    let rects = path_transform::as_rrects(&ltrb, &rx, &ry)?;
     let transforms = path_transform::as_rsx_trans(&rsx_trans)
         .ok_or_else(|| return savvy_err!("Failed to parse rsx_trans"))?;
+    let n = recycled_len(&[
+        ("ltrb", rects.len()),
+        ("rsx_trans", transforms.len()),
+        ("sigma", sigma.len()),
+        ("width", width.len()),
+        ("color", paint_attrs::colors_len(&color)),
+    ])?;
+    let rects = recycle("ltrb", rects, n)?;
+    let transforms = recycle("rsx_trans", transforms, n)?;
+    let sigma = recycle("sigma", sigma.as_slice_f64().to_vec(), n)?;
+    let width = recycle("width", width.as_slice_f64().to_vec(), n)?;
+    let color = paint_attrs::recycle_colors(&color, props.paint.color(), n)?;
 
-    let picture = read_picture_bytes(&curr_bytes)?;
     let mut props = props.clone();
-
-    let mut recorder = SkiaCanvas::setup(&size)?;
-    let canvas = recorder.start_recording();
-    picture.playback(canvas);
+    let canvas = target.recording_canvas()?;
 
     for (i, rect) in rects.iter().enumerate() {
         props.reset_blur(sigma[i]);
@@ -551,17 +1542,15 @@ fn sk_draw_rounded_rect(
         let rect = rect
             .clone()
             .transform(&skia_safe::Matrix::default().set_rsxform(&transforms[i]))
-            .ok_or_else(|| return savvy_err!("Failed to transform rrect at index {}", i))?;
+            .ok_or_else(|| return savvy_err!("Failed to transform rrect at index {}", i + 1))?;
         canvas.draw_rrect(&rect, &props.paint);
     }
-    let picture = recorder.finish_recording()?;
-    Ok(picture.into())
+    Ok(())
 }
 
 /// Draws outer and inner rounded rectangles
 ///
-/// @param size Canvas size.
-/// @param curr_bytes Current canvas state.
+/// @param target SkiaCanvas to draw on.
 /// @param props PaintAttrs.
 /// @param outer_ltrb Outer rectangles.
 /// @param outer_rx Axis lengths on X-axis of outer oval describing rounded corners.
@@ -573,13 +1562,9 @@ fn sk_draw_rounded_rect(
 /// @param sigma Blur sigma.
 /// @param width Stroke width.
 /// @param color Colors.
-/// @returns A raw vector of picture.
-/// @noRd
-#[savvy]
 fn sk_draw_diff_rect(
-    size: IntegerSexp,
-    curr_bytes: savvy::RawSexp,
-    props: PaintAttrs,
+    target: &mut SkiaCanvas,
+    props: &PaintAttrs,
     outer_ltrb: NumericSexp,
     outer_rx: NumericSexp,
     outer_ry: NumericSexp,
@@ -590,28 +1575,28 @@ fn sk_draw_diff_rect(
     sigma: NumericSexp,
     width: NumericSexp,
     color: NumericSexp,
-) -> savvy::Result<savvy::Sexp> {
-    let outer = path_transform::as_rrects(&outer_ltrb, &outer_rx, &outer_ry)
-        .ok_or_else(|| return savvy_err!("Failed to parse outer ltrb"))?;
-    let inner = path_transform::as_rrects(&inner_ltrb, &inner_rx, &inner_ry)
-        .ok_or_else(|| return savvy_err!("Failed to parse inner ltrb"))?;
-    let sigma = sigma.as_slice_f64();
-    let width = width.as_slice_f64();
-    let color = paint_attrs::num2colors(&color).unwrap_or_else(|| {
-        // if matrix is too small to take color, implicitly use paint color
-        let mut ret: Vec<skia_safe::Color> = Vec::new();
-        ret.resize(width.len(), props.paint.color());
-        ret
-    });
+) -> savvy::Result<()> {
+    let outer = path_transform::as_rrects(&outer_ltrb, &outer_rx, &outer_ry)?;
+    let inner = path_transform::as_rrects(&inner_ltrb, &inner_rx, &inner_ry)?;
     let transforms = path_transform::as_rsx_trans(&rsx_trans)
         .ok_or_else(|| return savvy_err!("Failed to parse rsx_trans"))?;
+    let n = recycled_len(&[
+        ("outer_ltrb", outer.len()),
+        ("inner_ltrb", inner.len()),
+        ("rsx_trans", transforms.len()),
+        ("sigma", sigma.len()),
+        ("width", width.len()),
+        ("color", paint_attrs::colors_len(&color)),
+    ])?;
+    let outer = recycle("outer_ltrb", outer, n)?;
+    let inner = recycle("inner_ltrb", inner, n)?;
+    let transforms = recycle("rsx_trans", transforms, n)?;
+    let sigma = recycle("sigma", sigma.as_slice_f64().to_vec(), n)?;
+    let width = recycle("width", width.as_slice_f64().to_vec(), n)?;
+    let color = paint_attrs::recycle_colors(&color, props.paint.color(), n)?;
 
-    let picture = read_picture_bytes(&curr_bytes)?;
     let mut props = props.clone();
-
-    let mut recorder = SkiaCanvas::setup(&size)?;
-    let canvas = recorder.start_recording();
-    picture.playback(canvas);
+    let canvas = target.recording_canvas()?;
 
     for (i, (outer, inner)) in outer.iter().zip(inner.iter()).enumerate() {
         props.reset_blur(sigma[i]);
@@ -620,18 +1605,17 @@ fn sk_draw_diff_rect(
         let outer = outer
             .clone()
             .transform(&skia_safe::Matrix::default().set_rsxform(&transforms[i]))
-            .ok_or_else(|| return savvy_err!("Failed to transform outer rrect at index {}", i))?;
+            .ok_or_else(|| return savvy_err!("Failed to transform outer rrect at index {}", i + 1))?;
         let inner = inner
             .clone()
             .transform(&skia_safe::Matrix::default().set_rsxform(&transforms[i]))
-            .ok_or_else(|| return savvy_err!("Failed to transform inner rrect at index {}", i))?;
+            .ok_or_else(|| return savvy_err!("Failed to transform inner rrect at index {}", i + 1))?;
         if outer.contains(inner.bounds()) {
             // Check is required because this drawing can be undefined
             canvas.draw_drrect(&outer, &inner, &props.paint);
         }
     }
-    let picture = recorder.finish_recording()?;
-    Ok(picture.into())
+    Ok(())
 }
 
 /// Draws atlas
@@ -639,24 +1623,17 @@ fn sk_draw_diff_rect(
 /// This function doesn't take `sprites` (offsets for the sprites) argument.
 /// The entire image is always used as a sprite.
 ///
-/// @param size Canvas size.
-/// @param curr_bytes Current canvas state.
+/// @param target SkiaCanvas to draw on.
 /// @param props PaintAttrs.
-/// @param png_bytes PNG bytes.
+/// @param png_bytes Encoded image bytes.
 /// @param rsx_trans RSX transforms for each sprite.
-/// @returns A raw vector of picture.
-/// @noRd
-#[savvy]
 fn sk_draw_atlas(
-    size: IntegerSexp,
-    curr_bytes: savvy::RawSexp,
-    props: PaintAttrs,
+    target: &mut SkiaCanvas,
+    props: &PaintAttrs,
     png_bytes: savvy::RawSexp,
     rsx_trans: NumericSexp,
-) -> savvy::Result<savvy::Sexp> {
-    let input = Data::new_copy(png_bytes.as_slice());
-    let image = Image::from_encoded_with_alpha_type(input, skia_safe::AlphaType::Premul)
-        .ok_or_else(|| return savvy_err!("Failed to read PNG as image"))?;
+) -> savvy::Result<()> {
+    let image = canvas::read_image_bytes(png_bytes.as_slice())?;
 
     // Does not need to check the number of rows here.
     let transforms = path_transform::as_rsx_trans(&rsx_trans)
@@ -666,12 +1643,7 @@ fn sk_draw_atlas(
         transforms.len(),
         skia_safe::Rect::new(0.0, 0.0, image.width() as f32, image.height() as f32),
     );
-    let picture = read_picture_bytes(&curr_bytes)?;
-
-    let mut recorder = SkiaCanvas::setup(&size)?;
-    let canvas = recorder.start_recording();
-    picture.playback(canvas);
-
+    let canvas = target.recording_canvas()?;
     canvas.draw_atlas(
         &image,
         &transforms,
@@ -682,50 +1654,35 @@ fn sk_draw_atlas(
         None,
         &props.paint,
     );
-    let picture = recorder.finish_recording()?;
-    Ok(picture.into())
+    Ok(())
 }
 
 /// Draws vertices
 ///
-/// @param size Canvas size.
-/// @param curr_bytes Current canvas state.
+/// @param target SkiaCanvas to draw on.
 /// @param props PaintAttrs.
 /// @param x X coordinates of points.
 /// @param y Y coordinates of points.
 /// @param sigma Blur sigma (scalar).
 /// @param color Colors of vertices.
 /// @param mode VertexMode.
-/// @returns A raw vector of picture.
-/// @noRd
-#[savvy]
 fn sk_draw_vertices(
-    size: IntegerSexp,
-    curr_bytes: savvy::RawSexp,
-    props: PaintAttrs,
+    target: &mut SkiaCanvas,
+    props: &PaintAttrs,
     x: NumericSexp,
     y: NumericSexp,
     sigma: NumericScalar,
     color: NumericSexp,
     mode: &paint_attrs::VertexMode,
-) -> savvy::Result<savvy::Sexp> {
+) -> savvy::Result<()> {
     let mode = paint_attrs::sk_vertex_mode(&mode);
-    let positions = path_transform::as_points(&x, &y);
+    let positions = path_transform::as_points(&x, &y)?;
     let sigma = sigma.as_f64();
-    let color = paint_attrs::num2colors(&color).unwrap_or_else(|| {
-        // if matrix is too small to take color, implicitly use paint color
-        let mut ret: Vec<skia_safe::Color> = Vec::new();
-        ret.resize(positions.len(), props.paint.color());
-        ret
-    });
+    let color = paint_attrs::recycle_colors(&color, props.paint.color(), positions.len())?;
     let vertices = skia_safe::Vertices::new_copy(mode, &positions, &positions, &color, None);
 
-    let picture = read_picture_bytes(&curr_bytes)?;
     let mut props = props.clone();
-
-    let mut recorder = SkiaCanvas::setup(&size)?;
-    let canvas = recorder.start_recording();
-    picture.playback(canvas);
+    let canvas = target.recording_canvas()?;
 
     props.reset_blur(sigma);
     canvas.draw_vertices(
@@ -733,8 +1690,7 @@ fn sk_draw_vertices(
         props.paint.blend_mode_or(skia_safe::BlendMode::DstOver),
         &props.paint,
     );
-    let picture = recorder.finish_recording()?;
-    Ok(picture.into())
+    Ok(())
 }
 
 /// Fills canvas with the specified color
@@ -1,21 +1,120 @@
-use savvy::{savvy, savvy_err};
+use savvy::{savvy, savvy_err, RawSexp, StringSexp};
+use skia_safe::textlayout::TypefaceFontProvider;
+use std::cell::RefCell;
+use std::sync::{Mutex, MutexGuard};
+
+/// Typefaces registered from font data and their family names,
+/// which take precedence over system fonts
+///
+/// The registry is process-wide, so that fonts registered in the R session
+/// are also found by drawing done on other threads.
+struct Registry {
+    fonts: Vec<(skia_safe::Typeface, String)>,
+    /// Incremented on each registration, so that cached font managers can tell they are stale
+    generation: u64,
+}
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    fonts: Vec::new(),
+    generation: 0,
+});
+
+thread_local! {
+    // `FontMgr` is not `Send`, so each thread keeps its own copy
+    static CACHED: RefCell<Option<(u64, skia_safe::FontMgr)>> = const { RefCell::new(None) };
+}
+
+fn registry() -> MutexGuard<'static, Registry> {
+    // The registry is updated in a single step, so it is never left in an invalid state
+    REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Returns a font manager of registered typefaces
+///
+/// The font manager is rebuilt only when a typeface has been registered since the last call.
+pub fn registered_fonts() -> skia_safe::FontMgr {
+    let registry = registry();
+    CACHED.with(|cached| {
+        let mut cached = cached.borrow_mut();
+        match cached.as_ref() {
+            Some((generation, mgr)) if *generation == registry.generation => mgr.clone(),
+            _ => {
+                let mut provider = TypefaceFontProvider::new();
+                for (typeface, family) in registry.fonts.iter() {
+                    provider.register_typeface(typeface.clone(), family.as_str());
+                }
+                let mgr: skia_safe::FontMgr = provider.into();
+                *cached = Some((registry.generation, mgr.clone()));
+                mgr
+            }
+        }
+    })
+}
 
 #[savvy]
 fn sk_list_families() -> savvy::Result<savvy::Sexp> {
+    let registered: Vec<String> = registered_fonts().family_names().collect();
     let mgr = skia_safe::FontMgr::new();
     let families = mgr.family_names();
-    let mut ret: Vec<String> = Vec::new();
+    let mut family: Vec<String> = Vec::new();
+    let mut source: Vec<&str> = Vec::new();
+    for f in registered {
+        family.push(f);
+        source.push("registered");
+    }
     for f in families {
-        ret.push(f);
+        family.push(f);
+        source.push("system");
     }
-    ret.try_into()
+    let mut out = savvy::OwnedListSexp::new(2, true)?;
+    out.set_name_and_value(0, "family", savvy::OwnedStringSexp::try_from(family)?)?;
+    out.set_name_and_value(1, "source", savvy::OwnedStringSexp::try_from(source)?)?;
+    Ok(out.into())
+}
+
+/// Registers a typeface from font data
+///
+/// @param data A raw vector of TTF, OTF, or WOFF2 data.
+/// @param alias Family name to register the typeface as.
+/// If empty, the family name of the font itself is used.
+/// A typeface of the same style registered under the same family name before is replaced.
+///
+/// @returns The family name that the typeface is registered as.
+/// @noRd
+#[savvy]
+fn sk_register_font(data: RawSexp, alias: StringSexp) -> savvy::Result<savvy::Sexp> {
+    let mgr = skia_safe::FontMgr::new();
+    let typeface = mgr
+        .new_from_data(data.as_slice(), None)
+        .ok_or_else(|| return savvy_err!("Failed to load font data"))?;
+    let family = match alias.iter().next() {
+        Some(alias) if !alias.is_empty() => alias.to_string(),
+        _ => typeface.family_name(),
+    };
+    let mut registry = registry();
+    let style = typeface.font_style();
+    match registry
+        .fonts
+        .iter_mut()
+        .find(|(t, f)| *f == family && t.font_style() == style)
+    {
+        Some(entry) => entry.0 = typeface,
+        None => registry.fonts.push((typeface, family.clone())),
+    }
+    registry.generation += 1;
+    family.try_into()
 }
 
 /// Takes a font family and style and returns a `skia_safe::Typeface`
+///
+/// Registered typefaces are looked up first, then system fonts.
 pub fn match_family_style(
     family: &str,
     style: skia_safe::FontStyle,
 ) -> anyhow::Result<skia_safe::Typeface, savvy::Error> {
+    if let Some(typeface) = registered_fonts().match_family_style(family, style) {
+        return Ok(typeface);
+    }
     let mgr = skia_safe::FontMgr::new();
     let typeface = mgr
         .match_family_style(family, style)
@@ -4,6 +4,8 @@ mod path_effect;
 mod shader;
 mod stroke;
 
+pub use image_filter::ImageFilter;
+
 use savvy::{savvy, savvy_err, NumericScalar, NumericSexp, StringSexp};
 use skia_safe::Paint;
 
@@ -140,6 +142,63 @@ pub fn assert_len(name: &str, expected: usize, actual: usize) -> anyhow::Result<
     }
 }
 
+/// Recycles values to `n` elements in the same way as R
+///
+/// A value of length 1 is repeated `n` times.
+/// Otherwise, the length must be equal to `n`.
+pub fn recycle<T: Clone>(name: &str, x: Vec<T>, n: usize) -> anyhow::Result<Vec<T>, savvy::Error> {
+    if x.len() == n {
+        Ok(x)
+    } else if x.len() == 1 {
+        Ok(vec![x[0].clone(); n])
+    } else {
+        Err(savvy_err!(
+            "{} must have 1 or {} elements, but has {}",
+            name,
+            n,
+            x.len()
+        ))
+    }
+}
+
+/// Returns the number of elements that arguments are recycled to
+///
+/// As in R, this is the longest length among the arguments.
+/// Each argument must have 1 element or that many elements.
+pub fn recycled_len(lens: &[(&str, usize)]) -> anyhow::Result<usize, savvy::Error> {
+    let n = lens.iter().map(|(_, len)| *len).max().unwrap_or(0);
+    for (name, len) in lens {
+        if *len != 1 && *len != n {
+            return Err(savvy_err!(
+                "{} must have 1 or {} elements, but has {}",
+                name,
+                n,
+                len
+            ));
+        }
+    }
+    Ok(n)
+}
+
+/// Returns the number of colors in a numeric vector
+///
+/// An empty vector counts as one color, since a fallback color is used for it.
+pub fn colors_len(color: &NumericSexp) -> usize {
+    (color.len() / 4).max(1)
+}
+
+/// Splits a numeric vector into colors and recycles them to `n` elements
+///
+/// If no color can be taken from `color`, `fallback` is used for all elements.
+pub fn recycle_colors(
+    color: &NumericSexp,
+    fallback: skia_safe::Color,
+    n: usize,
+) -> anyhow::Result<Vec<skia_safe::Color>, savvy::Error> {
+    let color = num2colors(color).unwrap_or_else(|| vec![fallback]);
+    recycle("color", color, n)
+}
+
 /// Get width, bbox and number of characters
 ///
 /// @param text Text strings.
@@ -181,6 +240,61 @@ fn sk_get_text_info(text: savvy::StringSexp, props: PaintAttrs) -> savvy::Result
     Ok(out.into())
 }
 
+/// Get outlines of text as SVG paths
+///
+/// @param text Text strings.
+/// @param props PaintAttrs.
+/// @param per_glyph Whether to return one path per glyph instead of one path per string.
+/// @returns A list.
+/// @noRd
+#[savvy]
+fn sk_text_to_svg(
+    text: savvy::StringSexp,
+    props: PaintAttrs,
+    per_glyph: savvy::LogicalSexp,
+) -> savvy::Result<savvy::Sexp> {
+    let typeface = font::match_family_style(props.font_family.as_str(), props.font_face)?;
+    let font = skia_safe::Font::from_typeface(&typeface, props.font_size);
+    let per_glyph = per_glyph.to_vec()[0];
+
+    let mut id: Vec<i32> = Vec::new();
+    let mut glyph: Vec<i32> = Vec::new();
+    let mut x: Vec<f64> = Vec::new();
+    let mut y: Vec<f64> = Vec::new();
+    let mut svg: Vec<String> = Vec::new();
+    for (i, txt) in text.iter().enumerate() {
+        let glyph_ids = font.str_to_glyphs_vec(txt);
+        let mut pos = vec![skia_safe::Point::default(); glyph_ids.len()];
+        font.get_pos(glyph_ids.as_slice(), &mut pos, None);
+
+        let mut builder = skia_safe::PathBuilder::new();
+        for (j, (g, p)) in glyph_ids.iter().zip(pos).enumerate() {
+            // Glyphs without outlines such as spaces and bitmaps result in empty paths
+            let path = font.get_path(*g).unwrap_or_default();
+            if per_glyph {
+                id.push(i as i32);
+                glyph.push(j as i32);
+                x.push(p.x as f64);
+                y.push(p.y as f64);
+                svg.push(skia_safe::utils::parse_path::to_svg(&path));
+            } else {
+                builder.add_path(&path.with_offset(p));
+            }
+        }
+        if !per_glyph {
+            id.push(i as i32);
+            svg.push(skia_safe::utils::parse_path::to_svg(&builder.detach()));
+        }
+    }
+    let mut out = savvy::OwnedListSexp::new(5, true)?;
+    out.set_name_and_value(0, "id", savvy::OwnedIntegerSexp::try_from(id)?)?;
+    out.set_name_and_value(1, "glyph", savvy::OwnedIntegerSexp::try_from(glyph)?)?;
+    out.set_name_and_value(2, "x", savvy::OwnedRealSexp::try_from(x)?)?;
+    out.set_name_and_value(3, "y", savvy::OwnedRealSexp::try_from(y)?)?;
+    out.set_name_and_value(4, "path", savvy::OwnedStringSexp::try_from(svg)?)?;
+    Ok(out.into())
+}
+
 /// BlurStyle (0-3)
 ///
 /// `BlurStyle` controls how a blur mask filter is applied to the shape.
@@ -1,10 +1,9 @@
 use super::{assert_len, num2colors};
-use crate::canvas::read_picture_bytes;
+use crate::canvas::{read_image_bytes, read_picture_bytes};
 use crate::path_transform::as_matrix;
 use crate::runtime_effect;
 
 use savvy::{savvy, savvy_err, LogicalSexp, NumericScalar, NumericSexp};
-use skia_safe::{Data, Image};
 
 /// @export
 #[savvy]
@@ -114,9 +113,7 @@ impl Shader {
     ) -> savvy::Result<Self> {
         let mat =
             as_matrix(&transform).ok_or_else(|| return savvy_err!("Failed to parse transform"))?;
-        let input = Data::new_copy(png_bytes.as_slice());
-        let image = Image::from_encoded_with_alpha_type(input, skia_safe::AlphaType::Premul)
-            .ok_or_else(|| return savvy_err!("Failed to read PNG as image"))?;
+        let image = read_image_bytes(png_bytes.as_slice())?;
         Ok(Shader {
             label: "image".to_string(),
             shader: image.to_shader(
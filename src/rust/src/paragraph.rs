@@ -0,0 +1,233 @@
+use crate::paint_attrs::{num2colors, recycle, PaintAttrs};
+
+use savvy::{savvy_err, NumericScalar, NumericSexp, StringSexp};
+use skia_safe::textlayout::{
+    FontCollection, Paragraph, ParagraphBuilder, ParagraphStyle, TextAlign, TextDecoration,
+    TextDecorationStyle, TextStyle,
+};
+
+/// Options for laying out paragraphs
+pub struct ParagraphOptions {
+    /// Width at which lines are wrapped
+    pub max_width: f32,
+    pub align: TextAlign,
+    /// Line height as a multiple of the font size. If `None`, the font's own height is used.
+    pub line_height: Option<f32>,
+    /// Maximum number of lines. If `None`, the number of lines is unlimited.
+    pub max_lines: Option<usize>,
+    /// Text appended to the last line when the paragraph exceeds `max_lines`
+    pub ellipsis: String,
+}
+
+impl ParagraphOptions {
+    /// Creates options from arguments passed from R
+    ///
+    /// `line_height` and `max_lines` are empty when they are not specified.
+    pub fn new(
+        max_width: NumericScalar,
+        align: StringSexp,
+        line_height: NumericSexp,
+        max_lines: NumericSexp,
+        ellipsis: StringSexp,
+    ) -> anyhow::Result<Self, savvy::Error> {
+        let max_width = max_width.as_f64();
+        if max_width.is_nan() || max_width <= 0.0 {
+            return Err(savvy_err!("max_width must be positive"));
+        }
+        Ok(ParagraphOptions {
+            max_width: max_width as f32,
+            align: text_align(align.to_vec()[0])?,
+            line_height: line_height.as_slice_f64().first().map(|h| *h as f32),
+            max_lines: max_lines
+                .as_slice_f64()
+                .first()
+                .map(|n| n.max(1.0) as usize),
+            ellipsis: ellipsis.to_vec()[0].to_string(),
+        })
+    }
+}
+
+/// Returns a font collection that resolves font families for paragraphs
+///
+/// Unlike text blobs, paragraphs fall back to other fonts
+/// for characters that are not supported by the specified font family.
+/// Registered typefaces are looked up before system fonts.
+pub fn font_collection() -> FontCollection {
+    let mut fonts = FontCollection::new();
+    fonts.set_asset_font_manager(crate::paint_attrs::font::registered_fonts());
+    fonts.set_default_font_manager(skia_safe::FontMgr::new(), None);
+    fonts
+}
+
+/// Returns a text style from font family, size, face and paint of PaintAttrs
+pub fn text_style(props: &PaintAttrs) -> TextStyle {
+    let mut style = TextStyle::new();
+    style.set_font_families(&[props.font_family.as_str()]);
+    style.set_font_size(props.font_size);
+    style.set_font_style(props.font_face);
+    style.set_foreground_paint(&props.paint);
+    style
+}
+
+/// Returns TextAlign from its name
+pub fn text_align(name: &str) -> anyhow::Result<TextAlign, savvy::Error> {
+    match name {
+        "left" => Ok(TextAlign::Left),
+        "right" => Ok(TextAlign::Right),
+        "center" => Ok(TextAlign::Center),
+        "justify" => Ok(TextAlign::Justify),
+        _ => Err(savvy_err!("Unknown text align: {}", name)),
+    }
+}
+
+/// Returns TextDecoration from its name
+fn text_decoration(name: &str) -> anyhow::Result<TextDecoration, savvy::Error> {
+    match name {
+        "none" => Ok(TextDecoration::NO_DECORATION),
+        "underline" => Ok(TextDecoration::UNDERLINE),
+        "overline" => Ok(TextDecoration::OVERLINE),
+        "line_through" => Ok(TextDecoration::LINE_THROUGH),
+        _ => Err(savvy_err!("Unknown text decoration: {}", name)),
+    }
+}
+
+/// Returns TextDecorationStyle from its name
+fn text_decoration_style(name: &str) -> anyhow::Result<TextDecorationStyle, savvy::Error> {
+    match name {
+        "solid" => Ok(TextDecorationStyle::Solid),
+        "double" => Ok(TextDecorationStyle::Double),
+        "dotted" => Ok(TextDecorationStyle::Dotted),
+        "dashed" => Ok(TextDecorationStyle::Dashed),
+        "wavy" => Ok(TextDecorationStyle::Wavy),
+        _ => Err(savvy_err!("Unknown text decoration style: {}", name)),
+    }
+}
+
+/// Takes a column of spans by name
+fn span_col<T>(spans: &savvy::ListSexp, name: &str) -> anyhow::Result<T, savvy::Error>
+where
+    T: TryFrom<savvy::Sexp, Error = savvy::Error>,
+{
+    let col = spans
+        .get(name)
+        .ok_or_else(|| return savvy_err!("spans must have '{}'", name))?;
+    T::try_from(col)
+}
+
+/// Returns text and styles of spans
+///
+/// `spans` is a list of columns with one element (or one color) per span.
+/// Each span is styled based on PaintAttrs,
+/// where `weight` and `italic` that are NA are taken from its font face.
+pub fn read_spans(
+    spans: &savvy::ListSexp,
+    props: &PaintAttrs,
+) -> anyhow::Result<Vec<(String, TextStyle)>, savvy::Error> {
+    let text: StringSexp = span_col(spans, "text")?;
+    let n = text.len();
+    if n == 0 {
+        return Err(savvy_err!("At least one span is required"));
+    }
+    let colors = |name: &str| -> anyhow::Result<Vec<skia_safe::Color>, savvy::Error> {
+        let col: NumericSexp = span_col(spans, name)?;
+        let col = num2colors(&col).ok_or_else(|| return savvy_err!("Failed to parse {}", name))?;
+        recycle(name, col, n)
+    };
+    let family: StringSexp = span_col(spans, "family")?;
+    let family = recycle("family", family.to_vec(), n)?;
+    let fontsize: NumericSexp = span_col(spans, "fontsize")?;
+    let fontsize = recycle("fontsize", fontsize.as_slice_f64().to_vec(), n)?;
+    let weight: NumericSexp = span_col(spans, "weight")?;
+    let weight = recycle("weight", weight.as_slice_f64().to_vec(), n)?;
+    let italic: NumericSexp = span_col(spans, "italic")?;
+    let italic = recycle("italic", italic.as_slice_f64().to_vec(), n)?;
+    let color = colors("color")?;
+    let letter_spacing: NumericSexp = span_col(spans, "letter_spacing")?;
+    let letter_spacing = recycle("letter_spacing", letter_spacing.as_slice_f64().to_vec(), n)?;
+    let background = colors("background")?;
+    let decoration: StringSexp = span_col(spans, "decoration")?;
+    let decoration = recycle("decoration", decoration.to_vec(), n)?;
+    let decoration_style: StringSexp = span_col(spans, "decoration_style")?;
+    let decoration_style = recycle("decoration_style", decoration_style.to_vec(), n)?;
+    let decoration_thickness: NumericSexp = span_col(spans, "decoration_thickness")?;
+    let decoration_thickness = recycle(
+        "decoration_thickness",
+        decoration_thickness.as_slice_f64().to_vec(),
+        n,
+    )?;
+    let decoration_color = colors("decoration_color")?;
+
+    let mut props = props.clone();
+    let mut ret = Vec::with_capacity(n);
+    for (i, t) in text.iter().enumerate() {
+        props.reset_color(color[i]);
+        let mut style = text_style(&props);
+        style.set_font_families(&[family[i]]);
+        style.set_font_size(fontsize[i] as f32);
+
+        let face = props.font_face;
+        let weight = if weight[i].is_nan() {
+            face.weight()
+        } else {
+            (weight[i] as i32).into()
+        };
+        let slant = match italic[i] {
+            v if v.is_nan() => face.slant(),
+            v if v != 0.0 => skia_safe::font_style::Slant::Italic,
+            _ => skia_safe::font_style::Slant::Upright,
+        };
+        style.set_font_style(skia_safe::FontStyle::new(weight, face.width(), slant));
+        style.set_letter_spacing(letter_spacing[i] as f32);
+
+        if background[i].a() > 0 {
+            let mut paint = skia_safe::Paint::default();
+            paint.set_color(background[i]);
+            style.set_background_paint(&paint);
+        }
+        style.set_decoration_type(text_decoration(decoration[i])?);
+        style.set_decoration_style(text_decoration_style(decoration_style[i])?);
+        style.set_decoration_thickness_multiplier(decoration_thickness[i] as f32);
+        // Transparent decoration color means the same color as text
+        style.set_decoration_color(if decoration_color[i].a() > 0 {
+            decoration_color[i]
+        } else {
+            color[i]
+        });
+
+        ret.push((t.to_string(), style));
+    }
+    Ok(ret)
+}
+
+/// Lays out spans of text as a paragraph wrapped at `options.max_width`
+///
+/// The paragraph takes its default style from the first span.
+pub fn layout(spans: &[(String, TextStyle)], options: &ParagraphOptions) -> Paragraph {
+    let with_height = |style: &TextStyle| {
+        let mut style = style.clone();
+        if let Some(height) = options.line_height {
+            style.set_height(height);
+            style.set_height_override(true);
+        }
+        style
+    };
+    let mut paragraph_style = ParagraphStyle::new();
+    if let Some((_, style)) = spans.first() {
+        paragraph_style.set_text_style(&with_height(style));
+    }
+    paragraph_style.set_text_align(options.align);
+    if let Some(max_lines) = options.max_lines {
+        paragraph_style.set_max_lines(max_lines);
+        paragraph_style.set_ellipsis(&options.ellipsis);
+    }
+
+    let mut builder = ParagraphBuilder::new(&paragraph_style, font_collection());
+    for (text, style) in spans {
+        builder.push_style(&with_height(style));
+        builder.add_text(text);
+        builder.pop();
+    }
+    let mut paragraph = builder.build();
+    paragraph.layout(options.max_width);
+    paragraph
+}
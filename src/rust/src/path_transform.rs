@@ -1,43 +1,59 @@
+use crate::paint_attrs::{recycle, recycled_len};
+
 use savvy::{savvy, savvy_err, NumericSexp, StringSexp};
 
 /// Returns Vec<skia_safe::Point>
-pub fn as_points(x: &NumericSexp, y: &NumericSexp) -> Vec<skia_safe::Point> {
-    let points = std::iter::zip(x.iter_f64(), y.iter_f64())
+///
+/// `x` and `y` are recycled to the longer length.
+pub fn as_points(
+    x: &NumericSexp,
+    y: &NumericSexp,
+) -> anyhow::Result<Vec<skia_safe::Point>, savvy::Error> {
+    let n = recycled_len(&[("x", x.len()), ("y", y.len())])?;
+    let x = recycle("x", x.as_slice_f64().to_vec(), n)?;
+    let y = recycle("y", y.as_slice_f64().to_vec(), n)?;
+    let points = std::iter::zip(x, y)
         .map(|(p0, p1)| skia_safe::Point::new(p0 as f32, p1 as f32))
         .collect::<Vec<skia_safe::Point>>();
-    points
+    Ok(points)
 }
 
 /// Converts NumericSexp to Vec<skia_safe::RRect>
+///
+/// Rectangles, `rx`, and `ry` are recycled to the longest length.
 pub fn as_rrects(
     rect: &NumericSexp,
     rx: &NumericSexp,
     ry: &NumericSexp,
-) -> Option<Vec<skia_safe::RRect>> {
-    let data = rect.as_slice_f64();
-    let rx = rx.as_slice_f64();
-    let ry = ry.as_slice_f64();
-    let mut ret: Vec<skia_safe::RRect> = Vec::new();
-    for (i, chunk) in data.chunks(4).enumerate() {
-        if chunk.len() == 4 {
-            let out = skia_safe::Rect::new(
+) -> anyhow::Result<Vec<skia_safe::RRect>, savvy::Error> {
+    if rect.len() % 4 != 0 {
+        return Err(savvy_err!("ltrb must have a multiple of 4 elements"));
+    }
+    let rects: Vec<skia_safe::Rect> = rect
+        .as_slice_f64()
+        .chunks_exact(4)
+        .map(|chunk| {
+            skia_safe::Rect::new(
                 chunk[0] as f32,
                 chunk[1] as f32,
                 chunk[2] as f32,
                 chunk[3] as f32,
-            );
-            ret.push(skia_safe::RRect::new_rect_xy(
-                out,
-                rx[i] as f32,
-                ry[i] as f32,
-            ));
-        }
-    }
-    if ret.is_empty() {
-        None
-    } else {
-        Some(ret)
+            )
+        })
+        .collect();
+    if rects.is_empty() {
+        return Err(savvy_err!("Failed to parse ltrb"));
     }
+    let n = recycled_len(&[("ltrb", rects.len()), ("rx", rx.len()), ("ry", ry.len())])?;
+    let rects = recycle("ltrb", rects, n)?;
+    let rx = recycle("rx", rx.as_slice_f64().to_vec(), n)?;
+    let ry = recycle("ry", ry.as_slice_f64().to_vec(), n)?;
+    let ret = rects
+        .into_iter()
+        .enumerate()
+        .map(|(i, rect)| skia_safe::RRect::new_rect_xy(rect, rx[i] as f32, ry[i] as f32))
+        .collect();
+    Ok(ret)
 }
 
 /// Converts NumericSexp to Vec<skia_safe::Matrix>
@@ -110,8 +126,8 @@ fn sk_matrix_map_point(
     dst_x: NumericSexp,
     dst_y: NumericSexp,
 ) -> savvy::Result<savvy::Sexp> {
-    let src = as_points(&src_x, &src_y);
-    let dst = as_points(&dst_x, &dst_y);
+    let src = as_points(&src_x, &src_y)?;
+    let dst = as_points(&dst_x, &dst_y)?;
     let matrix = skia_safe::Matrix::from_poly_to_poly(&src, &dst)
         .ok_or_else(|| return savvy_err!("Failed to map points"))?;
     let buffer = vec![
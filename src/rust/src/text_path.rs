@@ -0,0 +1,138 @@
+use savvy::savvy_err;
+use skia_safe::{ContourMeasure, ContourMeasureIter, Font, GlyphId, Point, RSXform, Vector};
+
+/// What to do with glyphs that do not fit within the path
+#[derive(Clone, Copy)]
+pub enum Overflow {
+    /// Glyphs beyond the ends of the path are not drawn
+    Clip,
+    /// Glyphs beyond the ends of the path are placed along the tangents at the ends
+    Extend,
+    /// The font size is reduced so that the text fits the path length,
+    /// and then glyphs beyond the ends of the path are not drawn
+    Shrink,
+}
+
+/// Options for placing text along a path
+pub struct TextPathOptions {
+    /// Distance along the path added to the aligned start position
+    pub start: f32,
+    /// Where to align the text: 0 for start, 0.5 for center, and 1 for end of the path
+    pub align: f32,
+    /// Distance to shift glyphs perpendicular to the path (positive is to the left of the path direction)
+    pub baseline_shift: f32,
+    pub overflow: Overflow,
+}
+
+impl TextPathOptions {
+    /// Creates options from names passed from R
+    pub fn new(
+        start: f64,
+        align: &str,
+        baseline_shift: f64,
+        overflow: &str,
+    ) -> anyhow::Result<Self, savvy::Error> {
+        let align = match align {
+            "left" => 0.0,
+            "center" => 0.5,
+            "right" => 1.0,
+            _ => return Err(savvy_err!("Unknown text align: {}", align)),
+        };
+        let overflow = match overflow {
+            "clip" => Overflow::Clip,
+            "extend" => Overflow::Extend,
+            "shrink" => Overflow::Shrink,
+            _ => return Err(savvy_err!("Unknown overflow: {}", overflow)),
+        };
+        Ok(TextPathOptions {
+            start: start as f32,
+            align,
+            baseline_shift: baseline_shift as f32,
+            overflow,
+        })
+    }
+}
+
+/// Contours of a path measured as one continuous line
+struct PathMeasure {
+    contours: Vec<ContourMeasure>,
+    length: f32,
+}
+
+impl PathMeasure {
+    fn new(path: &skia_safe::Path) -> Self {
+        let contours: Vec<ContourMeasure> = ContourMeasureIter::new(path, false, None).collect();
+        let length = contours.iter().map(|c| c.length()).sum();
+        PathMeasure { contours, length }
+    }
+
+    /// Returns position and unit tangent at `distance` from the start of the path
+    ///
+    /// Distances out of the path are extrapolated along the tangents at the ends.
+    fn pos_tan(&self, distance: f32) -> Option<(Point, Vector)> {
+        let first = self.contours.first()?;
+        if distance < 0.0 {
+            let (p, t) = first.pos_tan(0.0)?;
+            return Some((p + t * distance, t));
+        }
+        let mut rest = distance;
+        for contour in &self.contours {
+            if rest <= contour.length() {
+                return contour.pos_tan(rest);
+            }
+            rest -= contour.length();
+        }
+        let last = self.contours.last()?;
+        let (p, t) = last.pos_tan(last.length())?;
+        Some((p + t * rest, t))
+    }
+}
+
+/// Places glyphs of `text` along `path`
+///
+/// Each glyph is rotated to the tangent at the middle of its advance.
+/// Returns glyphs to be drawn and their transforms,
+/// and the font whose size may be reduced by `Overflow::Shrink`.
+pub fn place_glyphs(
+    font: &Font,
+    text: &str,
+    path: &skia_safe::Path,
+    options: &TextPathOptions,
+) -> anyhow::Result<(Font, Vec<GlyphId>, Vec<RSXform>), savvy::Error> {
+    let measure = PathMeasure::new(path);
+    if measure.contours.is_empty() {
+        return Err(savvy_err!("Path must have a length"));
+    }
+
+    let mut font = font.clone();
+    let glyphs = font.str_to_glyphs_vec(text);
+    let mut widths = vec![0.0; glyphs.len()];
+    font.get_widths(&glyphs, &mut widths);
+    let mut text_width: f32 = widths.iter().sum();
+    if matches!(options.overflow, Overflow::Shrink) && text_width > measure.length {
+        let ratio = measure.length / text_width;
+        font.set_size(font.size() * ratio);
+        widths.iter_mut().for_each(|w| *w *= ratio);
+        text_width = measure.length;
+    }
+
+    let mut advance = (measure.length - text_width) * options.align + options.start;
+    let mut ret_glyphs = Vec::with_capacity(glyphs.len());
+    let mut transforms = Vec::with_capacity(glyphs.len());
+    for (glyph, width) in glyphs.iter().zip(widths) {
+        let mid = advance + width / 2.0;
+        advance += width;
+        let outside = mid < 0.0 || mid > measure.length;
+        if outside && !matches!(options.overflow, Overflow::Extend) {
+            continue;
+        }
+        let Some((p, t)) = measure.pos_tan(mid) else {
+            continue;
+        };
+        let normal = Vector::new(t.y, -t.x);
+        let origin = p - t * (width / 2.0) + normal * options.baseline_shift;
+        ret_glyphs.push(*glyph);
+        transforms.push(RSXform::new(t.x, t.y, origin));
+    }
+    Ok((font, ret_glyphs, transforms))
+}
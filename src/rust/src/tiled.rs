@@ -0,0 +1,112 @@
+use savvy::savvy_err;
+use std::io::Write;
+
+use crate::canvas::validate_size;
+
+/// Returns the size of the output image scaled by `scale`
+///
+/// Unlike `validate_size`, this does not limit the total number of pixels,
+/// because the output is never allocated as a single surface.
+fn scaled_size(size: &[i32], scale: f64) -> anyhow::Result<(u32, u32), savvy::Error> {
+    if !scale.is_finite() || scale <= 0.0 {
+        return Err(savvy_err!(
+            "scale must be a positive number, but got {}",
+            scale
+        ));
+    }
+    crate::paint_attrs::assert_len("size", 2, size.len())?;
+    if size[0] == i32::MIN || size[1] == i32::MIN {
+        return Err(savvy_err!("Canvas size must not be NA"));
+    }
+    let width = (size[0] as f64 * scale).round();
+    let height = (size[1] as f64 * scale).round();
+    if width < 1.0 || height < 1.0 {
+        return Err(savvy_err!(
+            "Canvas size must be positive, but got {}x{}",
+            width,
+            height
+        ));
+    }
+    // PNG dimensions are limited to 2^31 - 1
+    if width > i32::MAX as f64 || height > i32::MAX as f64 {
+        return Err(savvy_err!("Canvas size {}x{} is too large", width, height));
+    }
+    Ok((width as u32, height as u32))
+}
+
+/// Renders a picture tile by tile and streams it into a PNG file
+///
+/// Only a surface of `tile_size` x `tile_size` and one strip of
+/// `tile_size` rows of the output image are kept in memory at a time.
+pub fn write_png_tiled(
+    path: &str,
+    size: Vec<i32>,
+    picture: &skia_safe::Picture,
+    tile_size: i32,
+    scale: f64,
+    compression: i32,
+) -> anyhow::Result<(), savvy::Error> {
+    let (width, height) = scaled_size(&size, scale)?;
+    let (tile, _) = validate_size(&[tile_size, tile_size])?;
+
+    let file = std::fs::File::create(path)
+        .map_err(|e| savvy_err!("Failed to create {}:\n {}", path, e))?;
+    let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_compression(match compression {
+        i32::MIN..=2 => png::Compression::Fast,
+        3..=6 => png::Compression::Default,
+        _ => png::Compression::Best,
+    });
+    let mut writer = encoder
+        .write_header()
+        .map_err(|e| savvy_err!("Failed to encode PNG:\n {}", e))?;
+    let mut stream = writer
+        .stream_writer()
+        .map_err(|e| savvy_err!("Failed to encode PNG:\n {}", e))?;
+
+    let mut surface = skia_safe::surfaces::raster_n32_premul((tile, tile))
+        .ok_or_else(|| return savvy_err!("Failed to create raster surface of {}x{}", tile, tile))?;
+    let info = skia_safe::ImageInfo::new(
+        (tile, tile),
+        skia_safe::ColorType::RGBA8888,
+        skia_safe::AlphaType::Unpremul,
+        None,
+    );
+    let tile_row_bytes = info.min_row_bytes();
+    let mut tile_pixels = vec![0u8; tile_row_bytes * tile as usize];
+
+    let (width, height, tile) = (width as usize, height as usize, tile as usize);
+    let mut strip = vec![0u8; width * 4 * tile];
+    for y0 in (0..height).step_by(tile) {
+        let rows = tile.min(height - y0);
+        for x0 in (0..width).step_by(tile) {
+            let cols = tile.min(width - x0);
+
+            let canvas = surface.canvas();
+            canvas.clear(skia_safe::Color::TRANSPARENT);
+            canvas.save();
+            canvas.translate((-(x0 as f32), -(y0 as f32)));
+            canvas.scale((scale as f32, scale as f32));
+            picture.playback(canvas);
+            canvas.restore();
+
+            if !surface.read_pixels(&info, tile_pixels.as_mut_slice(), tile_row_bytes, (0, 0)) {
+                return Err(savvy_err!("Failed to read pixels"));
+            }
+            for row in 0..rows {
+                let src = row * tile_row_bytes;
+                let dst = (row * width + x0) * 4;
+                strip[dst..dst + cols * 4].copy_from_slice(&tile_pixels[src..src + cols * 4]);
+            }
+        }
+        stream
+            .write_all(&strip[..rows * width * 4])
+            .map_err(|e| savvy_err!("Failed to encode PNG:\n {}", e))?;
+    }
+    stream
+        .finish()
+        .map_err(|e| savvy_err!("Failed to encode PNG:\n {}", e))?;
+    Ok(())
+}